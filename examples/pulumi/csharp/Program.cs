@@ -0,0 +1,132 @@
+using System.Collections.Generic;
+using Pulumi;
+using Pulumi.AzureNative.App;
+using Pulumi.AzureNative.App.Inputs;
+using Pulumi.AzureNative.ContainerRegistry;
+using Pulumi.AzureNative.Resources;
+using Docker = Pulumi.Docker;
+
+return await Deployment.RunAsync(() =>
+{
+    var resourceGroup = new ResourceGroup("rg");
+
+    var managedEnv = new ManagedEnvironment("managedEnvironment", new ManagedEnvironmentArgs
+    {
+        ResourceGroupName = resourceGroup.Name,
+    });
+
+    var registry = new Registry("registry", new RegistryArgs
+    {
+        ResourceGroupName = resourceGroup.Name,
+        Sku = new Pulumi.AzureNative.ContainerRegistry.Inputs.SkuArgs { Name = "Basic" },
+        AdminUserEnabled = true,
+    });
+
+    var credentials = ListRegistryCredentials.Invoke(new ListRegistryCredentialsInvokeArgs
+    {
+        ResourceGroupName = resourceGroup.Name,
+        RegistryName = registry.Name,
+    });
+    var adminUsername = credentials.Apply(c => c.Username!);
+    var adminPassword = credentials.Apply(c => c.Passwords[0].Value!);
+
+    var remixImage = new Docker.Image("remix", new Docker.ImageArgs
+    {
+        ImageName = Output.Format($"{registry.LoginServer}/remix:v1"),
+        Build = new Docker.Inputs.DockerBuildArgs { Context = "../frontend" },
+        Registry = new Docker.Inputs.RegistryArgs
+        {
+            Server = registry.LoginServer,
+            Username = adminUsername,
+            Password = adminPassword,
+        },
+    });
+
+    var service1Image = new Docker.Image("service1", new Docker.ImageArgs
+    {
+        ImageName = Output.Format($"{registry.LoginServer}/service1:v1"),
+        Build = new Docker.Inputs.DockerBuildArgs { Context = "../services/service1" },
+        Registry = new Docker.Inputs.RegistryArgs
+        {
+            Server = registry.LoginServer,
+            Username = adminUsername,
+            Password = adminPassword,
+        },
+    });
+
+    var frontendApp = new ContainerApp("frontend", new ContainerAppArgs
+    {
+        ResourceGroupName = resourceGroup.Name,
+        ManagedEnvironmentId = managedEnv.Id,
+        Configuration = new ConfigurationArgs
+        {
+            Dapr = new DaprArgs
+            {
+                Enabled = true,
+                AppPort = 8000,
+                AppId = "remix",
+            },
+            Ingress = new IngressArgs
+            {
+                External = true,
+                TargetPort = 8000,
+            },
+            Registries =
+            {
+                new RegistryCredentialsArgs
+                {
+                    Server = registry.LoginServer,
+                    Username = adminUsername,
+                    PasswordSecretRef = "pwd",
+                },
+            },
+            Secrets =
+            {
+                new SecretArgs { Name = "pwd", Value = adminPassword },
+            },
+        },
+        Template = new TemplateArgs
+        {
+            Containers =
+            {
+                new ContainerArgs
+                {
+                    Name = "remix",
+                    Image = remixImage.ImageName,
+                },
+            },
+        },
+    });
+
+    var service1 = new ContainerApp("service1", new ContainerAppArgs
+    {
+        ResourceGroupName = resourceGroup.Name,
+        ManagedEnvironmentId = managedEnv.Id,
+        Configuration = new ConfigurationArgs
+        {
+            Dapr = new DaprArgs
+            {
+                AppPort = 3000,
+                AppProtocol = "http",
+                Enabled = true,
+                AppId = "service1",
+            },
+        },
+        Template = new TemplateArgs
+        {
+            Containers = new InputList<ContainerArgs>
+            {
+                new ContainerArgs
+                {
+                    Name = "service1",
+                    Image = service1Image.ImageName,
+                },
+            },
+        },
+    });
+
+    return new Dictionary<string, object?>
+    {
+        ["url"] = Output.Format($"https://{frontendApp.LatestRevisionFqdn}"),
+    };
+});
@@ -1,3 +1,6 @@
+const frontendPort = 8000;
+const service2Port = 3001;
+
 const remixImage = new docker.Image("remix", {
     imageName: pulumi.interpolate`${registry.loginServer}/remix:v1`,
     build: { 
@@ -26,7 +29,10 @@ const service1Image = new docker.Image("service1", {
 const service2Image = new docker.Image("service2", {
     imageName: pulumi.interpolate`${registry.loginServer}/service2:v1`,
     build: { 
-        context: `../services/service2` 
+        context: `../services/service2`,
+        dockerfile: "../services/service2/Dockerfile.dev",
+        args: { NODE_VERSION: "18" },
+        target: "dev",
     },
     registry: {
         server : registry.loginServer,
@@ -43,12 +49,12 @@ const frontendApp = new app.ContainerApp("frontend", {
 
         dapr: {
             enabled: true,
-            appPort: 8000,
+            appPort: frontendPort,
             appId: "remix"
         },
         ingress: {
             external: true,
-            targetPort: 8000,
+            targetPort: frontendPort,
         },
      },
     template: {
@@ -92,7 +98,7 @@ const service2 = new app.ContainerApp("service2", {
     managedEnvironmentId: managedEnv.id,
     configuration: {
         dapr: {
-            appPort: 3001,
+            appPort: service2Port,
             appProtocol: "http",
             enabled: true,
             appId: "service2"
@@ -0,0 +1,26 @@
+import * as app from "@pulumi/azure-native/app";
+import { frontendPort, replicas, servicePort as port } from "./config";
+import * as images from "./images";
+import { resourceGroup } from "./registry";
+
+export const frontendApp = new app.ContainerApp("frontend", {
+    resourceGroupName: resourceGroup.name,
+    configuration: {
+        dapr: { enabled: true, appPort: frontendPort, appId: "remix" },
+        ingress: { external: true, targetPort: frontendPort },
+    },
+    template: {
+        containers: [{ name: "remix", image: images.remixImage.imageName }],
+        scale: { minReplicas: replicas, maxReplicas: 10 },
+    },
+});
+
+export const service1 = new app.ContainerApp("service1", {
+    resourceGroupName: resourceGroup.name,
+    configuration: {
+        dapr: { enabled: true, appPort: port, appId: "service1" },
+    },
+    template: {
+        containers: [{ name: "service1", image: images.service1Image.imageName }],
+    },
+});
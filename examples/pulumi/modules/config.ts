@@ -0,0 +1,7 @@
+import * as pulumi from "@pulumi/pulumi";
+
+const config = new pulumi.Config();
+
+export const frontendPort = config.getNumber("frontendPort") ?? 8000;
+export const servicePort = config.requireNumber("servicePort");
+export const replicas = config.getNumber("replicas") ?? 1;
@@ -0,0 +1,17 @@
+import * as docker from "@pulumi/docker";
+import * as pulumi from "@pulumi/pulumi";
+import { registry } from "./registry";
+
+export const remixImage = new docker.Image("remix", {
+    imageName: pulumi.interpolate`${registry.loginServer}/remix:v1`,
+    build: {
+        context: "../frontend",
+    },
+});
+
+export const service1Image = new docker.Image("service1", {
+    imageName: pulumi.interpolate`${registry.loginServer}/service1:v1`,
+    build: {
+        context: "../services/service1",
+    },
+});
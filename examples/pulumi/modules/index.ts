@@ -0,0 +1,3 @@
+import { frontendApp } from "./apps";
+
+export const url = pulumi.interpolate`https://${frontendApp.latestRevisionFqdn}`;
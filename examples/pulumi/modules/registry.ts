@@ -0,0 +1,9 @@
+import * as azure_native from "@pulumi/azure-native";
+
+export const resourceGroup = new azure_native.resources.ResourceGroup("rg");
+
+export const registry = new azure_native.containerregistry.Registry("registry", {
+    resourceGroupName: resourceGroup.name,
+    sku: { name: "Basic" },
+    adminUserEnabled: true,
+});
@@ -0,0 +1,123 @@
+import pulumi
+import pulumi_docker as docker
+from pulumi_azure_native import app, containerregistry, operationalinsights, resources
+
+resource_group = resources.ResourceGroup("rg")
+
+workspace = operationalinsights.Workspace(
+    "workspace",
+    resource_group_name=resource_group.name,
+    sku=operationalinsights.WorkspaceSkuArgs(name="PerGB2018"),
+    retention_in_days=30,
+)
+
+shared_key = pulumi.Output.all(resource_group.name, workspace.name).apply(
+    lambda args: operationalinsights.get_shared_keys(
+        resource_group_name=args[0], workspace_name=args[1]
+    ).primary_shared_key
+)
+
+managed_env = app.ManagedEnvironment(
+    "managedEnvironment",
+    resource_group_name=resource_group.name,
+    app_logs_configuration=app.AppLogsConfigurationArgs(
+        destination="log-analytics",
+        log_analytics_configuration=app.LogAnalyticsConfigurationArgs(
+            customer_id=workspace.customer_id, shared_key=shared_key
+        ),
+    ),
+)
+
+registry = containerregistry.Registry(
+    "registry",
+    resource_group_name=resource_group.name,
+    sku=containerregistry.SkuArgs(name="Basic"),
+    admin_user_enabled=True,
+)
+
+credentials = pulumi.Output.all(resource_group.name, registry.name).apply(
+    lambda args: containerregistry.list_registry_credentials(
+        resource_group_name=args[0], registry_name=args[1]
+    )
+)
+admin_username = credentials.username
+admin_password = credentials.passwords[0]["value"]
+
+remix_image = docker.Image(
+    "remix",
+    image_name=registry.login_server.apply(lambda server: f"{server}/remix:v1"),
+    build=docker.DockerBuildArgs(context="../frontend"),
+    registry=docker.ImageRegistryArgs(
+        server=registry.login_server,
+        username=admin_username,
+        password=admin_password,
+    ),
+)
+
+service1_image = docker.Image(
+    "service1",
+    image_name=registry.login_server.apply(lambda server: f"{server}/service1:v1"),
+    build=docker.DockerBuildArgs(context="../services/service1"),
+    registry=docker.ImageRegistryArgs(
+        server=registry.login_server,
+        username=admin_username,
+        password=admin_password,
+    ),
+)
+
+frontend_app = app.ContainerApp(
+    "frontend",
+    resource_group_name=resource_group.name,
+    managed_environment_id=managed_env.id,
+    configuration=app.ConfigurationArgs(
+        ingress=app.IngressArgs(
+            external=True,
+            target_port=8000,
+        ),
+        dapr=app.DaprArgs(
+            enabled=True,
+            app_port=8000,
+            app_id="remix",
+        ),
+        registries=[
+            app.RegistryCredentialsArgs(
+                server=registry.login_server,
+                username=admin_username,
+                password_secret_ref="pwd",
+            )
+        ],
+        secrets=[app.SecretArgs(name="pwd", value=admin_password)],
+    ),
+    template=app.TemplateArgs(
+        containers=[
+            app.ContainerArgs(
+                name="remix",
+                image=remix_image.image_name,
+            )
+        ],
+    ),
+)
+
+service1_app = app.ContainerApp(
+    "service1",
+    resource_group_name=resource_group.name,
+    managed_environment_id=managed_env.id,
+    configuration={
+        "dapr": {
+            "enabled": True,
+            "app_port": 3000,
+            "app_protocol": "http",
+            "app_id": "service1",
+        },
+    },
+    template={
+        "containers": [
+            {
+                "name": "service1",
+                "image": service1_image.image_name,
+            }
+        ],
+    },
+)
+
+pulumi.export("url", frontend_app.configuration.apply(lambda c: c.ingress.fqdn))
@@ -0,0 +1,117 @@
+variable "location" {
+  type    = string
+  default = "westeurope"
+}
+
+variable "registry_name" {
+  type    = string
+  default = "capps"
+}
+
+variable "tag" {
+  type = string
+}
+
+locals {
+  registry = azurerm_container_registry.registry.login_server
+}
+
+resource "azurerm_resource_group" "rg" {
+  name     = "rg"
+  location = var.location
+}
+
+resource "azurerm_container_registry" "registry" {
+  name                = var.registry_name
+  resource_group_name = azurerm_resource_group.rg.name
+  location            = azurerm_resource_group.rg.location
+  sku                 = "Basic"
+  admin_enabled       = true
+}
+
+resource "azurerm_container_app_environment" "managed_environment" {
+  name                = "managedEnvironment"
+  resource_group_name = azurerm_resource_group.rg.name
+  location            = azurerm_resource_group.rg.location
+}
+
+resource "azurerm_container_app" "frontend" {
+  name                         = "frontend"
+  container_app_environment_id = azurerm_container_app_environment.managed_environment.id
+  resource_group_name          = azurerm_resource_group.rg.name
+  revision_mode                = "Single"
+
+  ingress {
+    external_enabled = true
+    target_port      = 8000
+
+    traffic_weight {
+      percentage      = 100
+      latest_revision = true
+    }
+  }
+
+  dapr {
+    app_id   = "remix"
+    app_port = 8000
+  }
+
+  template {
+    container {
+      name   = "remix"
+      image  = "${local.registry}/remix:${var.tag}"
+      cpu    = 0.5
+      memory = "1Gi"
+    }
+  }
+}
+
+resource "azurerm_container_app" "service1" {
+  name                         = "service1"
+  container_app_environment_id = azurerm_container_app_environment.managed_environment.id
+  resource_group_name          = azurerm_resource_group.rg.name
+  revision_mode                = "Single"
+
+  dapr {
+    app_id   = "service1"
+    app_port = 3000
+  }
+
+  template {
+    container {
+      name   = "service1"
+      image  = "${local.registry}/service1:${var.tag}"
+      cpu    = 0.5
+      memory = "1Gi"
+
+      readiness_probe {
+        transport        = "HTTP"
+        port             = 3000
+        path             = "/health"
+        interval_seconds = 10
+      }
+    }
+  }
+}
+
+resource "azurerm_container_app_job" "nightly" {
+  name                         = "nightly"
+  container_app_environment_id = azurerm_container_app_environment.managed_environment.id
+  resource_group_name          = azurerm_resource_group.rg.name
+  location                     = azurerm_resource_group.rg.location
+  replica_timeout_in_seconds   = 1800
+
+  schedule_trigger_config {
+    cron_expression = "0 2 * * *"
+  }
+
+  template {
+    container {
+      name    = "nightly"
+      image   = "${local.registry}/service1:${var.tag}"
+      cpu     = 0.5
+      memory  = "1Gi"
+      command = ["node", "batch.js"]
+    }
+  }
+}
@@ -0,0 +1,1228 @@
+use log::{error, warn};
+use serde_json::{json, Map, Value};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{
+    call_function, get_apps, get_components, get_property, value_to_string, REGISTRY_TYPE,
+};
+use crate::pulumi;
+use crate::serializer::{ContainerAppConfiguration, ContainerImageBluePrint};
+
+// Guard against cyclic variable references
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, PartialEq, Clone)]
+enum Fragment {
+    Literal(String),
+    Source(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Identifier(String),
+    Number(i64),
+    String(Vec<Fragment>),
+    Symbol(String),
+    NewLine,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Segment {
+    Literal(String),
+    Expression(Expression),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Expression {
+    String(Vec<Segment>),
+    Number(i64),
+    Bool(bool),
+    Null,
+    Identifier(String),
+    Object(Vec<(String, Expression)>),
+    Array(Vec<Expression>),
+    For {
+        item: String,
+        index: Option<String>,
+        source: Box<Expression>,
+        condition: Option<Box<Expression>>,
+        body: Box<Expression>,
+    },
+    Property(Box<Expression>, String),
+    Index(Box<Expression>, Box<Expression>),
+    Call(Box<Expression>, Vec<Expression>),
+    Unary(String, Box<Expression>),
+    Binary(String, Box<Expression>, Box<Expression>),
+    Ternary(Box<Expression>, Box<Expression>, Box<Expression>),
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |items: &[Expression]| {
+            items
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        match self {
+            Expression::String(segments) => {
+                write!(f, "'")?;
+                for segment in segments {
+                    match segment {
+                        Segment::Literal(value) => write!(f, "{}", value)?,
+                        Segment::Expression(value) => write!(f, "${{{}}}", value)?,
+                    }
+                }
+                write!(f, "'")
+            }
+            Expression::Number(value) => write!(f, "{}", value),
+            Expression::Bool(value) => write!(f, "{}", value),
+            Expression::Null => write!(f, "null"),
+            Expression::Identifier(name) => write!(f, "{}", name),
+            Expression::Object(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value))
+                    .collect();
+                write!(f, "{{ {} }}", entries.join(", "))
+            }
+            Expression::Array(items) => write!(f, "[{}]", join(items)),
+            Expression::For {
+                item, source, body, ..
+            } => write!(f, "[for {} in {}: {}]", item, source, body),
+            Expression::Property(object, property) => write!(f, "{}.{}", object, property),
+            Expression::Index(object, index) => write!(f, "{}[{}]", object, index),
+            Expression::Call(callee, arguments) => write!(f, "{}({})", callee, join(arguments)),
+            Expression::Unary(operator, value) => write!(f, "{}{}", operator, value),
+            Expression::Binary(operator, left, right) => {
+                write!(f, "{} {} {}", left, operator, right)
+            }
+            Expression::Ternary(condition, left, right) => {
+                write!(f, "{} ? {} : {}", condition, left, right)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Resource {
+    symbol: String,
+    resource_type: String,
+    existing: bool,
+    condition: Option<Expression>,
+    body: Expression,
+}
+
+#[derive(Debug, PartialEq, Default)]
+struct Program {
+    parameters: HashMap<String, Option<Expression>>,
+    variables: HashMap<String, Expression>,
+    resources: Vec<Resource>,
+}
+
+fn read_string(chars: &[char], position: &mut usize) -> Result<Vec<Fragment>, String> {
+    let mut fragments = vec![];
+    let mut literal = String::new();
+
+    loop {
+        let c = *chars.get(*position).ok_or("Unterminated string")?;
+        *position += 1;
+
+        match c {
+            '\'' => break,
+            '\\' => {
+                let escaped = *chars.get(*position).ok_or("Unterminated string")?;
+                *position += 1;
+                match escaped {
+                    'n' => literal.push('\n'),
+                    'r' => literal.push('\r'),
+                    't' => literal.push('\t'),
+                    // Unicode escapes are written `\u{1F600}`
+                    'u' => {
+                        if chars.get(*position) != Some(&'{') {
+                            return Err("Invalid unicode escape".to_string());
+                        }
+                        let end = chars[*position..]
+                            .iter()
+                            .position(|c| *c == '}')
+                            .filter(|end| *end > 1)
+                            .ok_or("Invalid unicode escape")?;
+                        let code: String = chars[*position + 1..*position + end].iter().collect();
+                        let code = u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or("Invalid unicode escape")?;
+                        literal.push(code);
+                        *position += end + 1;
+                    }
+                    c => literal.push(c),
+                }
+            }
+            '$' if chars.get(*position) == Some(&'{') => {
+                *position += 1;
+                if !literal.is_empty() {
+                    fragments.push(Fragment::Literal(literal.clone()));
+                    literal.clear();
+                }
+
+                let mut source = String::new();
+                let mut depth = 1;
+                loop {
+                    let c = *chars.get(*position).ok_or("Unterminated interpolation")?;
+                    *position += 1;
+                    match c {
+                        '{' => depth += 1,
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        '\'' => {
+                            // Copy nested strings as is, their braces do not count
+                            source.push(c);
+                            loop {
+                                let c = *chars.get(*position).ok_or("Unterminated string")?;
+                                *position += 1;
+                                source.push(c);
+                                if c == '\\' {
+                                    source.push(*chars.get(*position).unwrap_or(&' '));
+                                    *position += 1;
+                                } else if c == '\'' {
+                                    break;
+                                }
+                            }
+                            continue;
+                        }
+                        _ => {}
+                    }
+                    source.push(c);
+                }
+                fragments.push(Fragment::Source(source));
+            }
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() || fragments.is_empty() {
+        fragments.push(Fragment::Literal(literal));
+    }
+
+    Ok(fragments)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while let Some(c) = chars.get(position).copied() {
+        let next = chars.get(position + 1).copied();
+        position += 1;
+
+        match c {
+            ' ' | '\t' | '\r' => {}
+            '\n' => {
+                if tokens.last() != Some(&Token::NewLine) {
+                    tokens.push(Token::NewLine);
+                }
+            }
+            '/' if next == Some('/') => {
+                while chars.get(position).filter(|c| **c != '\n').is_some() {
+                    position += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                position += 1;
+                while position < chars.len()
+                    && !(chars[position] == '*' && chars.get(position + 1) == Some(&'/'))
+                {
+                    position += 1;
+                }
+                position += 2;
+            }
+            '\'' if next == Some('\'') && chars.get(position + 1) == Some(&'\'') => {
+                // Multi-line strings have neither escapes nor interpolation
+                position += 2;
+                let start = position;
+                while position < chars.len() && !chars[position..].starts_with(&['\'', '\'', '\''])
+                {
+                    position += 1;
+                }
+                let value: String = chars[start..position.min(chars.len())].iter().collect();
+                // A leading line break is not part of the value
+                let value = value.strip_prefix('\n').unwrap_or(&value).to_string();
+                tokens.push(Token::String(vec![Fragment::Literal(value)]));
+                position += 3;
+            }
+            '\'' => tokens.push(Token::String(read_string(&chars, &mut position)?)),
+            c if c.is_ascii_digit() => {
+                let mut value = c.to_string();
+                while let Some(c) = chars.get(position).filter(|c| c.is_ascii_digit()) {
+                    value.push(*c);
+                    position += 1;
+                }
+                let number = value
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid number {}", value))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut value = c.to_string();
+                while let Some(c) = chars
+                    .get(position)
+                    .filter(|c| c.is_alphanumeric() || **c == '_')
+                {
+                    value.push(*c);
+                    position += 1;
+                }
+                tokens.push(Token::Identifier(value));
+            }
+            c => {
+                let pair: String = [c, next.unwrap_or(' ')].iter().collect();
+                let symbols = [
+                    "==", "!=", "<=", ">=", "&&", "||", "??", "::", ".?", "=~", "!~",
+                ];
+                if symbols.contains(&pair.as_str()) {
+                    tokens.push(Token::Symbol(pair));
+                    position += 1;
+                } else if "{}[](),:.?!=<>+-*/%@".contains(c) {
+                    tokens.push(Token::Symbol(c.to_string()));
+                } else {
+                    return Err(format!("Unexpected character {}", c));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn precedence(operator: &str) -> Option<u8> {
+    match operator {
+        "??" => Some(1),
+        "||" => Some(2),
+        "&&" => Some(3),
+        "==" | "!=" | "=~" | "!~" => Some(4),
+        "<" | ">" | "<=" | ">=" => Some(5),
+        "+" | "-" => Some(6),
+        "*" | "/" | "%" => Some(7),
+        _ => None,
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Result<Parser, String> {
+        Ok(Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if s == symbol)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Identifier(s)) if s == keyword)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let is_symbol = self.is_symbol(symbol);
+        if is_symbol {
+            self.position += 1;
+        }
+        is_symbol
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == symbol => Ok(()),
+            token => Err(format!("Expected {}, found {:?}", symbol, token)),
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Identifier(name)) => Ok(name),
+            token => Err(format!("Expected identifier, found {:?}", token)),
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek() == Some(&Token::NewLine) {
+            self.position += 1;
+        }
+    }
+
+    /***
+     * Skip everything until the end of the current statement
+     */
+    fn skip_statement(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::NewLine if depth == 0 => return,
+                Token::Symbol(s) if ["{", "[", "("].contains(&s.as_str()) => depth += 1,
+                Token::Symbol(s) if ["}", "]", ")"].contains(&s.as_str()) => {
+                    if depth == 0 {
+                        return;
+                    }
+                    depth -= 1
+                }
+                _ => {}
+            }
+            self.position += 1;
+        }
+    }
+
+    /***
+     * Skip a type annotation, eg: string, string[] or 'a' | 'b'
+     */
+    fn skip_type(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::NewLine if depth == 0 => return,
+                Token::Symbol(s) if s == "=" && depth == 0 => return,
+                Token::Symbol(s) if ["{", "[", "("].contains(&s.as_str()) => depth += 1,
+                Token::Symbol(s) if ["}", "]", ")"].contains(&s.as_str()) => depth -= 1,
+                _ => {}
+            }
+            self.position += 1;
+        }
+    }
+
+    fn parse_program(&mut self) -> Result<Program, String> {
+        let mut program = Program::default();
+
+        loop {
+            self.skip_newlines();
+            let keyword = match self.next() {
+                None => return Ok(program),
+                Some(Token::Symbol(s)) if s == "@" => {
+                    // Decorators have no effect locally
+                    self.parse_postfix()?;
+                    continue;
+                }
+                Some(Token::Identifier(keyword)) => keyword,
+                token => return Err(format!("Unexpected token {:?}", token)),
+            };
+
+            match keyword.as_str() {
+                "param" => {
+                    let name = self.expect_identifier()?;
+                    self.skip_type();
+                    let default = if self.eat_symbol("=") {
+                        Some(self.parse_expression()?)
+                    } else {
+                        None
+                    };
+                    program.parameters.insert(name, default);
+                }
+                "var" => {
+                    let name = self.expect_identifier()?;
+                    self.expect_symbol("=")?;
+                    let value = self.parse_expression()?;
+                    program.variables.insert(name, value);
+                }
+                "resource" => {
+                    let symbol = self.expect_identifier()?;
+                    let resource_type = match self.next() {
+                        Some(Token::String(fragments)) => match fragments.as_slice() {
+                            [Fragment::Literal(value)] => value.clone(),
+                            _ => return Err(format!("Invalid type for resource {}", symbol)),
+                        },
+                        token => return Err(format!("Expected resource type, found {:?}", token)),
+                    };
+                    let existing = self.is_keyword("existing");
+                    if existing {
+                        self.next();
+                    }
+                    self.expect_symbol("=")?;
+
+                    let condition = if self.is_keyword("if") {
+                        self.next();
+                        Some(self.parse_primary()?)
+                    } else {
+                        None
+                    };
+
+                    program.resources.push(Resource {
+                        symbol,
+                        resource_type: resource_type
+                            .split('@')
+                            .next()
+                            .unwrap_or_default()
+                            .to_string(),
+                        existing,
+                        condition,
+                        body: self.parse_expression()?,
+                    });
+                }
+                // Modules, outputs and other declarations are not emulated
+                _ => self.skip_statement(),
+            }
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        let condition = self.parse_binary(1)?;
+
+        if self.eat_symbol("?") {
+            self.skip_newlines();
+            let left = self.parse_expression()?;
+            self.skip_newlines();
+            self.expect_symbol(":")?;
+            self.skip_newlines();
+            let right = self.parse_expression()?;
+            return Ok(Expression::Ternary(
+                Box::new(condition),
+                Box::new(left),
+                Box::new(right),
+            ));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_binary(&mut self, minimum: u8) -> Result<Expression, String> {
+        let mut left = self.parse_unary()?;
+
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Symbol(s)) if precedence(s).filter(|p| *p >= minimum).is_some() => {
+                    s.clone()
+                }
+                _ => return Ok(left),
+            };
+            self.next();
+            self.skip_newlines();
+
+            let right = self.parse_binary(precedence(&operator).unwrap() + 1)?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        for operator in ["!", "-"] {
+            if self.eat_symbol(operator) {
+                let value = self.parse_unary()?;
+                return Ok(Expression::Unary(operator.to_string(), Box::new(value)));
+            }
+        }
+
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_primary()?;
+
+        loop {
+            if self.eat_symbol(".") || self.eat_symbol(".?") || self.eat_symbol("::") {
+                let property = self.expect_identifier()?;
+                expression = Expression::Property(Box::new(expression), property);
+            } else if self.eat_symbol("[") {
+                self.eat_symbol("?");
+                let index = self.parse_expression()?;
+                self.expect_symbol("]")?;
+                expression = Expression::Index(Box::new(expression), Box::new(index));
+            } else if self.eat_symbol("(") {
+                let mut arguments = vec![];
+                loop {
+                    self.skip_newlines();
+                    if self.eat_symbol(")") {
+                        break;
+                    }
+                    arguments.push(self.parse_expression()?);
+                    self.skip_newlines();
+                    self.eat_symbol(",");
+                }
+                expression = Expression::Call(Box::new(expression), arguments);
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::String(fragments)) => {
+                let segments = fragments
+                    .into_iter()
+                    .map(|fragment| match fragment {
+                        Fragment::Literal(value) => Ok(Segment::Literal(value)),
+                        Fragment::Source(source) => Ok(Segment::Expression(
+                            Parser::new(&source)?.parse_expression()?,
+                        )),
+                    })
+                    .collect::<Result<Vec<Segment>, String>>()?;
+                Ok(Expression::String(segments))
+            }
+            Some(Token::Identifier(name)) => match name.as_str() {
+                "true" => Ok(Expression::Bool(true)),
+                "false" => Ok(Expression::Bool(false)),
+                "null" => Ok(Expression::Null),
+                _ => Ok(Expression::Identifier(name)),
+            },
+            Some(Token::Symbol(s)) if s == "(" => {
+                self.skip_newlines();
+                let expression = self.parse_expression()?;
+                self.skip_newlines();
+                self.expect_symbol(")")?;
+                Ok(expression)
+            }
+            Some(Token::Symbol(s)) if s == "{" => self.parse_object(),
+            Some(Token::Symbol(s)) if s == "[" => {
+                self.skip_newlines();
+                if self.is_keyword("for") {
+                    self.next();
+                    self.parse_for()
+                } else {
+                    self.parse_array()
+                }
+            }
+            token => Err(format!("Unexpected token {:?}", token)),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Expression, String> {
+        let mut entries = vec![];
+
+        loop {
+            self.skip_newlines();
+            let key = match self.next() {
+                Some(Token::Symbol(s)) if s == "}" => return Ok(Expression::Object(entries)),
+                Some(Token::Symbol(s)) if s == "@" => {
+                    self.parse_postfix()?;
+                    continue;
+                }
+                Some(Token::Identifier(key)) => key,
+                Some(Token::String(fragments)) => match fragments.as_slice() {
+                    [Fragment::Literal(key)] => key.clone(),
+                    _ => return Err("Object keys cannot be interpolated".to_string()),
+                },
+                token => return Err(format!("Unexpected token {:?} in object", token)),
+            };
+
+            // Nested child resources are not emulated
+            if key == "resource" && !self.is_symbol(":") {
+                self.skip_statement();
+                continue;
+            }
+
+            self.expect_symbol(":")?;
+            self.skip_newlines();
+            let value = self.parse_expression()?;
+            entries.push((key, value));
+            self.eat_symbol(",");
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Expression, String> {
+        let mut items = vec![];
+
+        loop {
+            self.skip_newlines();
+            if self.eat_symbol("]") {
+                return Ok(Expression::Array(items));
+            }
+            items.push(self.parse_expression()?);
+            self.eat_symbol(",");
+        }
+    }
+
+    fn parse_for(&mut self) -> Result<Expression, String> {
+        let (item, index) = if self.eat_symbol("(") {
+            let item = self.expect_identifier()?;
+            self.expect_symbol(",")?;
+            let index = self.expect_identifier()?;
+            self.expect_symbol(")")?;
+            (item, Some(index))
+        } else {
+            (self.expect_identifier()?, None)
+        };
+
+        match self.next() {
+            Some(Token::Identifier(s)) if s == "in" => {}
+            token => return Err(format!("Expected in, found {:?}", token)),
+        }
+        let source = self.parse_expression()?;
+        self.expect_symbol(":")?;
+        self.skip_newlines();
+
+        let condition = if self.is_keyword("if") {
+            self.next();
+            Some(Box::new(self.parse_primary()?))
+        } else {
+            None
+        };
+
+        let body = self.parse_expression()?;
+        self.skip_newlines();
+        self.expect_symbol("]")?;
+
+        Ok(Expression::For {
+            item,
+            index,
+            source: Box::new(source),
+            condition,
+            body: Box::new(body),
+        })
+    }
+}
+
+type Scope = HashMap<String, Value>;
+
+struct Evaluator<'a> {
+    program: &'a Program,
+    depth: Cell<usize>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(program: &'a Program) -> Evaluator<'a> {
+        Evaluator {
+            program,
+            depth: Cell::new(0),
+        }
+    }
+
+    fn evaluate_or_source(&self, expression: &Expression, scope: &Scope) -> Value {
+        // Unresolved parts of a string are kept as interpolations
+        if let Expression::String(segments) = expression {
+            let mut output = String::new();
+            for segment in segments {
+                match segment {
+                    Segment::Literal(value) => output.push_str(value),
+                    Segment::Expression(value) => match self.evaluate(value, scope) {
+                        Some(v) => output.push_str(&value_to_string(&v)),
+                        None => {
+                            warn!("Cannot resolve expression {}", value);
+                            output.push_str(&format!("${{{}}}", value));
+                        }
+                    },
+                }
+            }
+            return Value::String(output);
+        }
+
+        match self.evaluate(expression, scope) {
+            Some(value) => value,
+            None => {
+                warn!("Cannot resolve expression {}", expression);
+                Value::String(expression.to_string())
+            }
+        }
+    }
+
+    fn nested<T>(&self, evaluate: impl FnOnce() -> Option<T>) -> Option<T> {
+        if self.depth.get() > MAX_DEPTH {
+            return None;
+        }
+        self.depth.set(self.depth.get() + 1);
+        let value = evaluate();
+        self.depth.set(self.depth.get() - 1);
+        value
+    }
+
+    fn resolve(&self, name: &str, scope: &Scope) -> Option<Value> {
+        if let Some(value) = scope.get(name) {
+            return Some(value.clone());
+        }
+
+        if let Some(variable) = self.program.variables.get(name) {
+            return self.nested(|| self.evaluate(variable, &Scope::new()));
+        }
+
+        if let Some(parameter) = self.program.parameters.get(name) {
+            return self.nested(|| self.evaluate(parameter.as_ref()?, &Scope::new()));
+        }
+
+        let resource = self
+            .program
+            .resources
+            .iter()
+            .find(|resource| resource.symbol == name)?;
+        self.nested(|| self.reference(resource))
+    }
+
+    /***
+     * Symbolic reference to a resource, only values known before deployment are available
+     */
+    fn reference(&self, resource: &Resource) -> Option<Value> {
+        let name = match &resource.body {
+            Expression::Object(entries) => entries
+                .iter()
+                .find(|(key, _)| key == "name")
+                .and_then(|(_, value)| self.evaluate(value, &Scope::new())),
+            _ => None,
+        }?;
+        let name = value_to_string(&name);
+
+        let mut properties = Map::new();
+        if resource.resource_type.eq_ignore_ascii_case(REGISTRY_TYPE) {
+            properties.insert(
+                "loginServer".to_string(),
+                Value::String(format!("{}.azurecr.io", name.to_lowercase())),
+            );
+        }
+
+        Some(json!({
+            "name": name,
+            "type": resource.resource_type,
+            "id": format!("/providers/{}/{}", resource.resource_type, name),
+            "properties": properties,
+        }))
+    }
+
+    fn evaluate(&self, expression: &Expression, scope: &Scope) -> Option<Value> {
+        match expression {
+            Expression::String(segments) => {
+                let mut output = String::new();
+                for segment in segments {
+                    match segment {
+                        Segment::Literal(value) => output.push_str(value),
+                        Segment::Expression(value) => {
+                            output.push_str(&value_to_string(&self.evaluate(value, scope)?))
+                        }
+                    }
+                }
+                Some(Value::String(output))
+            }
+            Expression::Number(value) => Some(Value::from(*value)),
+            Expression::Bool(value) => Some(Value::Bool(*value)),
+            Expression::Null => Some(Value::Null),
+            Expression::Identifier(name) => self.resolve(name, scope),
+            Expression::Object(entries) => Some(Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), self.evaluate_or_source(value, scope)))
+                    .collect(),
+            )),
+            Expression::Array(items) => Some(Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.evaluate_or_source(item, scope))
+                    .collect(),
+            )),
+            Expression::For {
+                item,
+                index,
+                source,
+                condition,
+                body,
+            } => {
+                let source = self.evaluate(source, scope)?;
+                let mut output = vec![];
+
+                for (position, value) in source.as_array()?.iter().enumerate() {
+                    let mut scope = scope.clone();
+                    scope.insert(item.to_string(), value.clone());
+                    if let Some(index) = index {
+                        scope.insert(index.to_string(), Value::from(position));
+                    }
+
+                    let is_filtered = match condition {
+                        Some(condition) => {
+                            self.evaluate(condition, &scope) == Some(Value::Bool(false))
+                        }
+                        None => false,
+                    };
+                    if !is_filtered {
+                        output.push(self.evaluate_or_source(body, &scope));
+                    }
+                }
+
+                Some(Value::Array(output))
+            }
+            Expression::Property(object, property) => {
+                get_property(&self.evaluate(object, scope)?, property).cloned()
+            }
+            Expression::Index(object, index) => {
+                let object = self.evaluate(object, scope)?;
+                match self.evaluate(index, scope)? {
+                    Value::Number(n) => object.get(n.as_u64()? as usize).cloned(),
+                    Value::String(property) => get_property(&object, &property).cloned(),
+                    _ => None,
+                }
+            }
+            Expression::Call(callee, arguments) => {
+                let name = match callee.as_ref() {
+                    Expression::Identifier(name) => name,
+                    // Namespaced functions, eg: sys.concat(...)
+                    Expression::Property(namespace, name) if matches!(namespace.as_ref(), Expression::Identifier(n) if n == "sys" || n == "az") => {
+                        name
+                    }
+                    _ => return None,
+                };
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate(argument, scope))
+                    .collect::<Option<Vec<Value>>>()?;
+
+                call_function(name, &arguments)
+            }
+            Expression::Unary(operator, value) => {
+                let value = self.evaluate(value, scope)?;
+                match operator.as_str() {
+                    "!" => Some(Value::Bool(!value.as_bool()?)),
+                    "-" => value.as_i64()?.checked_neg().map(Value::from),
+                    _ => None,
+                }
+            }
+            Expression::Binary(operator, left, right) => {
+                if operator == "??" {
+                    return self
+                        .evaluate(left, scope)
+                        .filter(|value| !value.is_null())
+                        .or_else(|| self.evaluate(right, scope));
+                }
+
+                let left = self.evaluate(left, scope)?;
+                let right = self.evaluate(right, scope)?;
+                match operator.as_str() {
+                    "==" => Some(Value::Bool(left == right)),
+                    "!=" => Some(Value::Bool(left != right)),
+                    "=~" => Some(Value::Bool(
+                        value_to_string(&left).eq_ignore_ascii_case(&value_to_string(&right)),
+                    )),
+                    "!~" => Some(Value::Bool(
+                        !value_to_string(&left).eq_ignore_ascii_case(&value_to_string(&right)),
+                    )),
+                    "&&" => Some(Value::Bool(left.as_bool()? && right.as_bool()?)),
+                    "||" => Some(Value::Bool(left.as_bool()? || right.as_bool()?)),
+                    operator => {
+                        let (left, right) = (left.as_i64()?, right.as_i64()?);
+                        match operator {
+                            "<" => Some(Value::Bool(left < right)),
+                            ">" => Some(Value::Bool(left > right)),
+                            "<=" => Some(Value::Bool(left <= right)),
+                            ">=" => Some(Value::Bool(left >= right)),
+                            // Overflows are left unresolved
+                            "+" => left.checked_add(right).map(Value::from),
+                            "-" => left.checked_sub(right).map(Value::from),
+                            "*" => left.checked_mul(right).map(Value::from),
+                            "/" => left.checked_div(right).map(Value::from),
+                            "%" => left.checked_rem(right).map(Value::from),
+                            _ => None,
+                        }
+                    }
+                }
+            }
+            Expression::Ternary(condition, left, right) => {
+                match self.evaluate(condition, scope)? {
+                    Value::Bool(true) => self.evaluate(left, scope),
+                    Value::Bool(false) => self.evaluate(right, scope),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /***
+     * Deployed resources with their evaluated body, loops are expanded
+     */
+    fn get_resources(&self) -> Vec<Value> {
+        let scope = Scope::new();
+
+        self.program
+            .resources
+            .iter()
+            .filter(|resource| !resource.existing)
+            .filter(|resource| match &resource.condition {
+                Some(condition) => self.evaluate(condition, &scope) != Some(Value::Bool(false)),
+                None => true,
+            })
+            .flat_map(|resource| {
+                let bodies = match self.evaluate_or_source(&resource.body, &scope) {
+                    Value::Array(values) => values,
+                    value => vec![value],
+                };
+
+                bodies.into_iter().filter_map(|mut body| {
+                    body.as_object_mut()?.insert(
+                        "type".to_string(),
+                        Value::String(resource.resource_type.clone()),
+                    );
+                    Some(body)
+                })
+            })
+            .collect()
+    }
+}
+
+fn parse_program(input: &str) -> Result<Program, String> {
+    Parser::new(input)?.parse_program()
+}
+
+pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let program = match parse_program(input) {
+        Ok(program) => program,
+        Err(e) => {
+            error!("{}", e);
+            return Err(e);
+        }
+    };
+
+    let resources = Evaluator::new(&program).get_resources();
+    if resources.is_empty() {
+        return Err("Resources need to be defined".to_string());
+    }
+
+    let apps = get_apps(&resources)?;
+    // Bicep files only reference already pushed images, there is nothing to build
+    let images: Vec<ContainerImageBluePrint> = vec![];
+
+    let components = get_components(&resources);
+
+    let services = pulumi::build_configuration(apps, images, components);
+
+    match services {
+        Some(val) => Ok(val),
+        None => Err("No container to deserialize".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = r#"
+@description('Name of the registry')
+param registryName string = 'capps'
+param appPort int = 3000
+param tag string
+param services array = [
+  'service1'
+  'service2'
+]
+
+var environmentName = 'env-${toLower('DEV')}'
+
+/* Shared registry */
+resource acr 'Microsoft.ContainerRegistry/registries@2022-02-01-preview' = {
+  name: registryName
+  sku: { name: 'Basic' }
+}
+
+resource env 'Microsoft.App/managedEnvironments@2022-03-01' = {
+  name: environmentName
+}
+
+resource apps 'Microsoft.App/containerApps@2022-03-01' = [for (service, i) in services: {
+  name: service
+  properties: {
+    managedEnvironmentId: env.id
+    configuration: {
+      ingress: i == 0 ? {
+        external: true
+        targetPort: 80
+      } : null
+      dapr: {
+        enabled: true
+        appId: service
+        appPort: appPort + i
+      }
+    }
+    template: {
+      containers: [
+        {
+          name: service // container name
+          image: '${acr.properties.loginServer}/${service}:${tag}'
+        }
+      ]
+    }
+  }
+}]
+
+output fqdn string = apps[0].properties.configuration.ingress.fqdn
+"#;
+
+    #[test]
+    fn test_tokenize() {
+        let output = tokenize("x: 'a${b}c' // comment\n'''\nraw ${x}'''");
+        let expected = vec![
+            Token::Identifier("x".to_string()),
+            Token::Symbol(":".to_string()),
+            Token::String(vec![
+                Fragment::Literal("a".to_string()),
+                Fragment::Source("b".to_string()),
+                Fragment::Literal("c".to_string()),
+            ]),
+            Token::NewLine,
+            Token::String(vec![Fragment::Literal("raw ${x}".to_string())]),
+        ];
+        assert_eq!(Ok(expected), output);
+
+        let output = tokenize("'it\\'s ${concat('}', x)}'");
+        let expected = vec![Token::String(vec![
+            Fragment::Literal("it's ".to_string()),
+            Fragment::Source("concat('}', x)".to_string()),
+        ])];
+        assert_eq!(Ok(expected), output);
+
+        assert!(tokenize("'unterminated").is_err());
+
+        let output = tokenize("'\\u{41}'");
+        assert_eq!(
+            Ok(vec![Token::String(vec![Fragment::Literal(
+                "A".to_string()
+            )])]),
+            output
+        );
+        assert!(tokenize("'\\u}'").is_err());
+        assert!(tokenize("'\\u{}'").is_err());
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let output = Parser::new("a.b[0] == 'x' ? f(1, 2) : null")
+            .unwrap()
+            .parse_expression();
+        let expected = Expression::Ternary(
+            Box::new(Expression::Binary(
+                "==".to_string(),
+                Box::new(Expression::Index(
+                    Box::new(Expression::Property(
+                        Box::new(Expression::Identifier("a".to_string())),
+                        "b".to_string(),
+                    )),
+                    Box::new(Expression::Number(0)),
+                )),
+                Box::new(Expression::String(vec![Segment::Literal("x".to_string())])),
+            )),
+            Box::new(Expression::Call(
+                Box::new(Expression::Identifier("f".to_string())),
+                vec![Expression::Number(1), Expression::Number(2)],
+            )),
+            Box::new(Expression::Null),
+        );
+        assert_eq!(Ok(expected), output);
+
+        let output = Parser::new("{\n a: 1\n b: [\n 1, 2\n ]\n}")
+            .unwrap()
+            .parse_expression();
+        let expected = Expression::Object(vec![
+            ("a".to_string(), Expression::Number(1)),
+            (
+                "b".to_string(),
+                Expression::Array(vec![Expression::Number(1), Expression::Number(2)]),
+            ),
+        ]);
+        assert_eq!(Ok(expected), output);
+    }
+
+    #[test]
+    fn test_parse_program() {
+        let program = parse_program(TEMPLATE).unwrap();
+
+        assert_eq!(4, program.parameters.len());
+        assert_eq!(None, program.parameters["tag"]);
+        assert_eq!(1, program.variables.len());
+        assert_eq!(
+            vec!["acr", "env", "apps"],
+            program
+                .resources
+                .iter()
+                .map(|resource| resource.symbol.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(
+            "Microsoft.App/containerApps",
+            program.resources[2].resource_type
+        );
+
+        assert!(parse_program("resource x = {}").is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let program = Program::default();
+        let evaluator = Evaluator::new(&program);
+        let evaluate = |input: &str| {
+            let expression = Parser::new(input).unwrap().parse_expression().unwrap();
+            evaluator.evaluate(&expression, &Scope::new())
+        };
+
+        assert_eq!(Some(json!(7)), evaluate("1 + 2 * 3"));
+        // Overflows are left unresolved
+        assert_eq!(None, evaluate("9223372036854775807 + 1"));
+        assert_eq!(None, evaluate("9223372036854775807 * 2"));
+        assert_eq!(None, evaluate("1 / 0"));
+    }
+
+    #[test]
+    fn test_get_resources() {
+        let program = parse_program(TEMPLATE).unwrap();
+        let resources = Evaluator::new(&program).get_resources();
+
+        assert_eq!(4, resources.len());
+        assert_eq!(json!("env-dev"), resources[1]["name"]);
+
+        let app = &resources[2]["properties"];
+        assert_eq!(
+            json!("/providers/Microsoft.App/managedEnvironments/env-dev"),
+            app["managedEnvironmentId"]
+        );
+        // Unresolved parameters are kept as interpolation
+        assert_eq!(
+            json!("capps.azurecr.io/service1:${tag}"),
+            app["template"]["containers"][0]["image"]
+        );
+        assert_eq!(json!(3000), app["configuration"]["dapr"]["appPort"]);
+        assert_eq!(json!(80), app["configuration"]["ingress"]["targetPort"]);
+
+        let app = &resources[3]["properties"];
+        assert_eq!(json!(3001), app["configuration"]["dapr"]["appPort"]);
+        assert_eq!(Value::Null, app["configuration"]["ingress"]);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let output = deserialize("param x string");
+        assert_eq!(Err("Resources need to be defined".to_string()), output);
+
+        let input = TEMPLATE.replace("param tag string", "param tag string = 'v1'");
+        let output = deserialize(&input).unwrap();
+
+        assert_eq!(4, output.len());
+        assert_eq!(
+            Some("capps.azurecr.io/service1:v1".to_string()),
+            output[0].image
+        );
+        assert_eq!(Some(vec!["80:3000".to_string()]), output[0].ports);
+        assert_eq!(
+            Some("capps.azurecr.io/service2:v1".to_string()),
+            output[2].image
+        );
+
+        // Parameters without default value are left to compose interpolation
+        let output = deserialize(TEMPLATE).unwrap();
+
+        assert_eq!(4, output.len());
+        assert_eq!(
+            Some("capps.azurecr.io/service1:${tag}".to_string()),
+            output[0].image
+        );
+    }
+}
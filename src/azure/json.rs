@@ -0,0 +1,506 @@
+use log::{error, warn};
+use serde_json::{json, Map, Value};
+
+use super::{
+    call_function, find_key, get_apps, get_components, get_property, is_type, value_to_string,
+    REGISTRY_TYPE,
+};
+use crate::pulumi;
+use crate::serializer::{ContainerAppConfiguration, ContainerImageBluePrint};
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Identifier(String),
+    String(String),
+    Number(i64),
+    OpenParen,
+    CloseParen,
+    OpenBracket,
+    CloseBracket,
+    Comma,
+    Dot,
+}
+
+/***
+ * ARM template expression, eg: [concat(parameters('registry'), '/app:v1')]
+ */
+#[derive(Debug, PartialEq, Clone)]
+enum Expression {
+    String(String),
+    Number(i64),
+    Call(String, Vec<Expression>),
+    Property(Box<Expression>, String),
+    Index(Box<Expression>, Box<Expression>),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => {}
+            '(' => tokens.push(Token::OpenParen),
+            ')' => tokens.push(Token::CloseParen),
+            '[' => tokens.push(Token::OpenBracket),
+            ']' => tokens.push(Token::CloseBracket),
+            ',' => tokens.push(Token::Comma),
+            '.' => tokens.push(Token::Dot),
+            '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // Quotes are escaped by doubling them
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            value.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => value.push(c),
+                        None => return Err(format!("Unterminated string in {}", input)),
+                    }
+                }
+                tokens.push(Token::String(value));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut value = c.to_string();
+                while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    value.push(*c);
+                    chars.next();
+                }
+                let number = value
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid number {} in {}", value, input))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut value = c.to_string();
+                while let Some(c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                    value.push(*c);
+                    chars.next();
+                }
+                tokens.push(Token::Identifier(value));
+            }
+            c => return Err(format!("Unexpected character {} in {}", c, input)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("Expected {:?}, found {:?}", expected, token)),
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<Expression>, String> {
+        let mut arguments = vec![];
+        if self.peek() == Some(&Token::CloseParen) {
+            self.next();
+            return Ok(arguments);
+        }
+
+        loop {
+            arguments.push(self.parse_expression()?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::CloseParen) => return Ok(arguments),
+                token => return Err(format!("Unexpected token {:?} in arguments", token)),
+            }
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        let mut expression = match self.next() {
+            Some(Token::String(value)) => Expression::String(value),
+            Some(Token::Number(value)) => Expression::Number(value),
+            Some(Token::Identifier(name)) => {
+                self.expect(Token::OpenParen)?;
+                Expression::Call(name, self.parse_arguments()?)
+            }
+            token => return Err(format!("Unexpected token {:?}", token)),
+        };
+
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.next();
+                    match self.next() {
+                        Some(Token::Identifier(property)) => {
+                            expression = Expression::Property(Box::new(expression), property)
+                        }
+                        token => return Err(format!("Expected property, found {:?}", token)),
+                    }
+                }
+                Some(Token::OpenBracket) => {
+                    self.next();
+                    let index = self.parse_expression()?;
+                    self.expect(Token::CloseBracket)?;
+                    expression = Expression::Index(Box::new(expression), Box::new(index));
+                }
+                _ => return Ok(expression),
+            }
+        }
+    }
+}
+
+fn parse_expression(input: &str) -> Result<Expression, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+
+    let expression = parser.parse_expression()?;
+
+    match parser.peek() {
+        None => Ok(expression),
+        Some(token) => Err(format!("Unexpected token {:?} in {}", token, input)),
+    }
+}
+
+/***
+ * Evaluation context of an ARM template
+ */
+struct Template<'a> {
+    parameters: Option<&'a Map<String, Value>>,
+    variables: Option<&'a Map<String, Value>>,
+    registries: Vec<String>,
+}
+
+impl<'a> Template<'a> {
+    fn new(template: &'a Value) -> Template<'a> {
+        Template {
+            parameters: template.get("parameters").and_then(|v| v.as_object()),
+            variables: template.get("variables").and_then(|v| v.as_object()),
+            registries: vec![],
+        }
+    }
+
+    fn evaluate_value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => self.evaluate_string(s),
+            Value::Array(values) => {
+                Value::Array(values.iter().map(|v| self.evaluate_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.to_string(), self.evaluate_value(v)))
+                    .collect(),
+            ),
+            v => v.clone(),
+        }
+    }
+
+    fn evaluate_string(&self, input: &str) -> Value {
+        // A leading "[[" escapes a literal string starting with a bracket
+        if input.starts_with("[[") {
+            return Value::String(input[1..].to_string());
+        }
+
+        if !(input.starts_with('[') && input.ends_with(']')) {
+            return Value::String(input.to_string());
+        }
+
+        let evaluated = parse_expression(&input[1..input.len() - 1])
+            .ok()
+            .and_then(|expression| self.evaluate(&expression));
+
+        match evaluated {
+            Some(value) => value,
+            None => {
+                warn!("Cannot resolve expression {}", input);
+                Value::String(input.to_string())
+            }
+        }
+    }
+
+    fn evaluate(&self, expression: &Expression) -> Option<Value> {
+        match expression {
+            Expression::String(value) => Some(Value::String(value.to_string())),
+            Expression::Number(value) => Some(Value::from(*value)),
+            Expression::Property(object, property) => {
+                get_property(&self.evaluate(object)?, property).cloned()
+            }
+            Expression::Index(object, index) => {
+                let object = self.evaluate(object)?;
+                match self.evaluate(index)? {
+                    Value::Number(n) => object.get(n.as_u64()? as usize).cloned(),
+                    Value::String(property) => get_property(&object, &property).cloned(),
+                    _ => None,
+                }
+            }
+            Expression::Call(name, arguments) => self.call(name, arguments),
+        }
+    }
+
+    fn call(&self, name: &str, arguments: &[Expression]) -> Option<Value> {
+        let arguments: Vec<Value> = arguments
+            .iter()
+            .map(|argument| self.evaluate(argument))
+            .collect::<Option<Vec<Value>>>()?;
+
+        let string_argument = |index: usize| arguments.get(index).map(value_to_string);
+
+        match name.to_lowercase().as_str() {
+            "parameters" => {
+                let parameter = find_key(self.parameters?, &string_argument(0)?)?;
+                get_property(parameter, "defaultValue").map(|v| self.evaluate_value(v))
+            }
+            "variables" => {
+                let variable = find_key(self.variables?, &string_argument(0)?)?;
+                Some(self.evaluate_value(variable))
+            }
+            "reference" => {
+                let id = string_argument(0)?;
+                let name = match id.to_lowercase().find(&REGISTRY_TYPE.to_lowercase()) {
+                    Some(_) => id.rsplit('/').next()?.to_string(),
+                    None => id,
+                };
+
+                self.registries
+                    .iter()
+                    .find(|registry| registry.eq_ignore_ascii_case(&name))
+                    .map(|registry| {
+                        json!({ "loginServer": format!("{}.azurecr.io", registry.to_lowercase()) })
+                    })
+            }
+            _ => call_function(name, &arguments),
+        }
+    }
+}
+
+fn get_resources(resources: Option<&Value>) -> Vec<&Value> {
+    // Symbolic name templates (languageVersion 2.0) declare resources as an object
+    let resources: Vec<&Value> = match resources {
+        Some(Value::Array(values)) => values.iter().collect(),
+        Some(Value::Object(map)) => map.values().collect(),
+        _ => vec![],
+    };
+
+    resources
+        .into_iter()
+        .flat_map(|resource| {
+            let mut nested = get_resources(resource.get("resources"));
+            nested.insert(0, resource);
+            nested
+        })
+        .collect()
+}
+
+pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let value: Value = match serde_json::from_str(input) {
+        Ok(v) => v,
+        Err(e) => {
+            error!("{}", e);
+            return Err(e.to_string());
+        }
+    };
+
+    let resources = get_resources(value.get("resources"));
+    if resources.is_empty() {
+        return Err("Resources need to be defined".to_string());
+    }
+
+    let mut template = Template::new(&value);
+    template.registries = resources
+        .iter()
+        .filter(|resource| is_type(resource, REGISTRY_TYPE))
+        .filter_map(|resource| resource.get("name"))
+        .map(|name| value_to_string(&template.evaluate_value(name)))
+        .collect();
+
+    let resources: Vec<Value> = resources
+        .iter()
+        .map(|resource| template.evaluate_value(resource))
+        .collect();
+
+    let apps = get_apps(&resources)?;
+    // ARM templates only reference already pushed images, there is nothing to build
+    let images: Vec<ContainerImageBluePrint> = vec![];
+
+    let components = get_components(&resources);
+
+    let services = pulumi::build_configuration(apps, images, components);
+
+    match services {
+        Some(val) => Ok(val),
+        None => Err("No container to deserialize".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = r#"{
+        "$schema": "https://schema.management.azure.com/schemas/2019-04-01/deploymentTemplate.json#",
+        "contentVersion": "1.0.0.0",
+        "parameters": {
+            "registryName": { "type": "string", "defaultValue": "MyRegistry" },
+            "appPort": { "type": "int", "defaultValue": 3000 },
+            "tag": { "type": "string" }
+        },
+        "variables": {
+            "environmentName": "[concat('env-', 'dev')]",
+            "imageName": "[format('{0}/service1:{1}', reference(resourceId('Microsoft.ContainerRegistry/registries', parameters('registryName'))).loginServer, 'v1')]"
+        },
+        "resources": [
+            {
+                "type": "Microsoft.ContainerRegistry/registries",
+                "apiVersion": "2022-02-01-preview",
+                "name": "[parameters('registryName')]"
+            },
+            {
+                "type": "Microsoft.App/managedEnvironments",
+                "apiVersion": "2022-03-01",
+                "name": "[variables('environmentName')]"
+            },
+            {
+                "type": "Microsoft.App/containerApps",
+                "apiVersion": "2022-03-01",
+                "name": "service1",
+                "properties": {
+                    "managedEnvironmentId": "[resourceId('Microsoft.App/managedEnvironments', variables('environmentName'))]",
+                    "configuration": {
+                        "ingress": { "external": true, "targetPort": 80 },
+                        "dapr": { "enabled": true, "appId": "service1", "appPort": "[parameters('appPort')]" }
+                    },
+                    "template": {
+                        "containers": [{ "name": "service1", "image": "[variables('imageName')]" }]
+                    }
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_expression() {
+        let output = parse_expression("concat(parameters('a'), 'it''s')[0].b");
+        let expected = Expression::Property(
+            Box::new(Expression::Index(
+                Box::new(Expression::Call(
+                    "concat".to_string(),
+                    vec![
+                        Expression::Call(
+                            "parameters".to_string(),
+                            vec![Expression::String("a".to_string())],
+                        ),
+                        Expression::String("it's".to_string()),
+                    ],
+                )),
+                Box::new(Expression::Number(0)),
+            )),
+            "b".to_string(),
+        );
+        assert_eq!(Ok(expected), output);
+
+        let output = parse_expression("concat('a'");
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn test_evaluate_string() {
+        let value: Value = serde_json::from_str(TEMPLATE).unwrap();
+        let mut template = Template::new(&value);
+        template.registries = vec!["MyRegistry".to_string()];
+
+        assert_eq!(
+            json!("myregistry.azurecr.io/service1:v1"),
+            template.evaluate_string("[variables('imageName')]")
+        );
+        assert_eq!(
+            json!(3000),
+            template.evaluate_string("[parameters('appPort')]")
+        );
+        assert_eq!(json!("[literal]"), template.evaluate_string("[[literal]"));
+        assert_eq!(json!("plain"), template.evaluate_string("plain"));
+        assert_eq!(
+            json!("A-b"),
+            template.evaluate_string("[format('{0}-{1}', toUpper('a'), 'b')]")
+        );
+        // Parameters without default value are kept unresolved
+        assert_eq!(
+            json!("[parameters('tag')]"),
+            template.evaluate_string("[parameters('tag')]")
+        );
+    }
+
+    #[test]
+    fn test_get_resources() {
+        let value = json!({
+            "resources": [
+                { "type": "Microsoft.App/managedEnvironments", "resources": [
+                    { "type": "Microsoft.App/containerApps" }
+                ]}
+            ]
+        });
+        let output = get_resources(value.get("resources"));
+        assert_eq!(2, output.len());
+
+        let value = json!({
+            "resources": { "app": { "type": "Microsoft.App/containerApps" } }
+        });
+        let output = get_resources(value.get("resources"));
+        assert_eq!(1, output.len());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let output = deserialize(r#"{ "resources": [] }"#);
+        assert_eq!(Err("Resources need to be defined".to_string()), output);
+
+        let output = deserialize(TEMPLATE).unwrap();
+        assert_eq!(2, output.len());
+        assert_eq!(
+            Some("myregistry.azurecr.io/service1:v1".to_string()),
+            output[0].image
+        );
+        assert_eq!(Some(vec!["80:3000".to_string()]), output[0].ports);
+    }
+
+    #[test]
+    fn test_deserialize_with_unresolved_parameter() {
+        // `port` has no default value, the target port cannot be resolved and is dropped
+        let template = json!({
+            "parameters": { "port": { "type": "int" } },
+            "resources": [{
+                "type": "Microsoft.App/containerApps",
+                "name": "service1",
+                "properties": {
+                    "configuration": {
+                        "ingress": { "external": true, "targetPort": "[parameters('port')]" }
+                    },
+                    "template": {
+                        "containers": [{ "name": "service1", "image": "capps.azurecr.io/service1:v1" }]
+                    }
+                }
+            }]
+        });
+
+        let output = deserialize(&template.to_string()).unwrap();
+
+        assert_eq!(1, output.len());
+        assert_eq!(
+            Some("capps.azurecr.io/service1:v1".to_string()),
+            output[0].image
+        );
+    }
+}
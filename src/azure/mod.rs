@@ -0,0 +1,391 @@
+pub mod bicep;
+pub mod json;
+use crate::serializer::{
+    ContainerAppBluePrint, ContainerAppConfiguration, DaprComponentBluePrint, Language, Serializer,
+};
+use log::{error, warn};
+use serde_json::{Map, Value};
+
+const CONTAINER_APP_TYPE: &str = "Microsoft.App/containerApps";
+const JOB_TYPE: &str = "Microsoft.App/jobs";
+const MANAGED_ENVIRONMENT_TYPE: &str = "Microsoft.App/managedEnvironments";
+// Child resources of an environment can be declared with the short type `daprComponents`
+const DAPR_COMPONENT_TYPE: &str = "daprComponents";
+const REGISTRY_TYPE: &str = "Microsoft.ContainerRegistry/registries";
+// Largest count of the `range` function accepted by ARM
+const RANGE_LIMIT: i64 = 10000;
+
+pub struct Azure {
+    language: Language,
+    pub resources: Option<Vec<ContainerAppConfiguration>>,
+}
+
+impl Azure {
+    pub fn new(language: Language) -> Option<Azure> {
+        match language {
+            Language::Json | Language::Bicep => Some(Azure {
+                language,
+                resources: None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Serializer for Azure {
+    type Output = Azure;
+    fn deserialize_value(&mut self, input: &str) -> Result<&Self, String> {
+        match self.language {
+            Language::Json => match json::deserialize(input) {
+                Ok(value) => {
+                    self.resources = Some(value);
+                    Ok(self)
+                }
+                Err(err) => Err(err),
+            },
+            Language::Bicep => match bicep::deserialize(input) {
+                Ok(value) => {
+                    self.resources = Some(value);
+                    Ok(self)
+                }
+                Err(err) => Err(err),
+            },
+            _ => {
+                error!("Language not supported");
+                Err("An error occured".to_string())
+            }
+        }
+    }
+}
+
+fn is_type(resource: &Value, resource_type: &str) -> bool {
+    match resource.get("type").and_then(|t| t.as_str()) {
+        Some(t) => t.eq_ignore_ascii_case(resource_type),
+        None => false,
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Null => "".to_string(),
+        v => v.to_string(),
+    }
+}
+
+fn find_key<'a>(map: &'a Map<String, Value>, property: &str) -> Option<&'a Value> {
+    // ARM property access is case insensitive
+    map.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(property))
+        .map(|(_, v)| v)
+}
+
+fn get_property<'a>(value: &'a Value, property: &str) -> Option<&'a Value> {
+    find_key(value.as_object()?, property)
+}
+
+/***
+ * Template functions shared by ARM and Bicep, None when it cannot be resolved locally
+ */
+fn call_function(name: &str, arguments: &[Value]) -> Option<Value> {
+    let string_argument = |index: usize| arguments.get(index).map(value_to_string);
+
+    match name.to_lowercase().as_str() {
+        "concat" => {
+            if !arguments.is_empty() && arguments.iter().all(|a| a.is_array()) {
+                Some(Value::Array(
+                    arguments
+                        .iter()
+                        .flat_map(|a| a.as_array().unwrap().clone())
+                        .collect(),
+                ))
+            } else {
+                Some(Value::String(
+                    arguments.iter().map(value_to_string).collect(),
+                ))
+            }
+        }
+        "format" => {
+            let mut output = string_argument(0)?;
+            for (index, argument) in arguments.iter().skip(1).enumerate() {
+                output = output.replace(&format!("{{{}}}", index), &value_to_string(argument));
+            }
+            Some(Value::String(output))
+        }
+        "tolower" => Some(Value::String(string_argument(0)?.to_lowercase())),
+        "toupper" => Some(Value::String(string_argument(0)?.to_uppercase())),
+        "string" => Some(Value::String(string_argument(0)?)),
+        "int" => string_argument(0)?.parse::<i64>().ok().map(Value::from),
+        "replace" => Some(Value::String(
+            string_argument(0)?.replace(&string_argument(1)?, &string_argument(2)?),
+        )),
+        "split" => Some(Value::Array(
+            string_argument(0)?
+                .split(&string_argument(1)?)
+                .map(|part| Value::String(part.to_string()))
+                .collect(),
+        )),
+        "length" => match arguments.first()? {
+            Value::Array(values) => Some(Value::from(values.len())),
+            Value::Object(map) => Some(Value::from(map.len())),
+            value => Some(Value::from(value_to_string(value).chars().count())),
+        },
+        "first" => arguments.first()?.as_array()?.first().cloned(),
+        "last" => arguments.first()?.as_array()?.last().cloned(),
+        "empty" => match arguments.first()? {
+            Value::Array(values) => Some(Value::Bool(values.is_empty())),
+            Value::Object(map) => Some(Value::Bool(map.is_empty())),
+            value => Some(Value::Bool(value_to_string(value).is_empty())),
+        },
+        "range" => {
+            let start = arguments.first()?.as_i64()?;
+            let count = arguments.get(1)?.as_i64()?;
+            if !(0..=RANGE_LIMIT).contains(&count) {
+                warn!(
+                    "Range count {} is not between 0 and {}, it is left unresolved",
+                    count, RANGE_LIMIT
+                );
+                return None;
+            }
+            Some(Value::Array(
+                (start..start.checked_add(count)?)
+                    .map(Value::from)
+                    .collect(),
+            ))
+        }
+        "union" => {
+            let mut output = Map::new();
+            for argument in arguments {
+                output.extend(argument.as_object()?.clone());
+            }
+            Some(Value::Object(output))
+        }
+        "equals" => Some(Value::Bool(arguments.first()? == arguments.get(1)?)),
+        "if" => match arguments.first()? {
+            Value::Bool(true) => arguments.get(1).cloned(),
+            Value::Bool(false) => arguments.get(2).cloned(),
+            _ => None,
+        },
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        "null" => Some(Value::Null),
+        "resourceid" => {
+            // Optional subscription and resource group come before the resource type
+            let position = arguments
+                .iter()
+                .position(|a| value_to_string(a).contains('/'))?;
+            let names: Vec<String> = arguments
+                .iter()
+                .skip(position + 1)
+                .map(value_to_string)
+                .collect();
+
+            Some(Value::String(format!(
+                "/providers/{}/{}",
+                value_to_string(&arguments[position]),
+                names.join("/")
+            )))
+        }
+        _ => None,
+    }
+}
+
+fn get_names(resources: &[Value], resource_type: &str) -> Vec<String> {
+    resources
+        .iter()
+        .filter(|resource| is_type(resource, resource_type))
+        .filter_map(|resource| resource.get("name"))
+        .map(value_to_string)
+        .collect()
+}
+
+/***
+ * Dapr components of the managed environments, their names are prefixed by the environment, eg: `env/statestore`
+ */
+fn get_components(resources: &[Value]) -> Vec<DaprComponentBluePrint> {
+    resources
+        .iter()
+        .filter(|resource| {
+            resource
+                .get("type")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| t.rsplit('/').next() == Some(DAPR_COMPONENT_TYPE))
+        })
+        .filter_map(|resource| {
+            let name = resource.get("name").map(value_to_string)?;
+            let mut properties = resource.get("properties")?.to_owned();
+            properties["componentName"] =
+                Value::String(name.rsplit('/').next().unwrap_or_default().to_string());
+
+            serde_json::from_value(properties)
+                .map_err(|e| warn!("Dapr component {} cannot be parsed: {}", name, e))
+                .ok()
+        })
+        .collect()
+}
+
+/***
+ * Extract container apps and jobs from already evaluated resources
+ */
+fn get_apps(resources: &[Value]) -> Result<Vec<ContainerAppBluePrint>, String> {
+    let environments = get_names(resources, MANAGED_ENVIRONMENT_TYPE);
+
+    resources
+        .iter()
+        .filter(|resource| is_type(resource, CONTAINER_APP_TYPE) || is_type(resource, JOB_TYPE))
+        .map(|resource| {
+            let properties = resource
+                .get("properties")
+                .ok_or("Container app properties need to be defined")?;
+
+            // Jobs reference their environment with `environmentId`
+            let environment_id = properties
+                .get("managedEnvironmentId")
+                .or_else(|| properties.get("environmentId"))
+                .map(value_to_string)
+                .unwrap_or_default();
+            let is_declared = environments
+                .iter()
+                .any(|name| environment_id.rsplit('/').next() == Some(name.as_str()));
+            if !is_declared {
+                warn!(
+                    "Managed environment {} is not declared in this template",
+                    environment_id
+                );
+            }
+
+            serde_json::from_value(properties.to_owned()).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::serializer::{
+        ConfigurationBluePrint, ContainerBluePrint, DaprBluePrint, IngressBluePrint,
+        TemplateBluePrint, VolumeBluePrint, VolumeMountBluePrint,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_call_function() {
+        let output = call_function("concat", &[json!("a"), json!(1), json!("b")]);
+        assert_eq!(Some(json!("a1b")), output);
+
+        let output = call_function("concat", &[json!(["a"]), json!(["b"])]);
+        assert_eq!(Some(json!(["a", "b"])), output);
+
+        let output = call_function("format", &[json!("{0}/{1}"), json!("a"), json!("b")]);
+        assert_eq!(Some(json!("a/b")), output);
+
+        let output = call_function(
+            "resourceId",
+            &[json!("Microsoft.App/managedEnvironments"), json!("env")],
+        );
+        assert_eq!(
+            Some(json!("/providers/Microsoft.App/managedEnvironments/env")),
+            output
+        );
+
+        let output = call_function("range", &[json!(1), json!(3)]);
+        assert_eq!(Some(json!([1, 2, 3])), output);
+        let output = call_function("range", &[json!(0), json!(2147483647)]);
+        assert_eq!(None, output);
+        let output = call_function("range", &[json!(1), json!(-1)]);
+        assert_eq!(None, output);
+        let output = call_function("range", &[json!(i64::MAX), json!(3)]);
+        assert_eq!(None, output);
+
+        let output = call_function("uniqueString", &[json!("a")]);
+        assert_eq!(None, output);
+    }
+
+    #[test]
+    fn test_get_apps() {
+        let resources = vec![
+            json!({
+                "type": "Microsoft.App/managedEnvironments",
+                "name": "env"
+            }),
+            json!({
+                "type": "Microsoft.App/containerApps",
+                "name": "service1",
+                "properties": {
+                    "managedEnvironmentId": "/providers/Microsoft.App/managedEnvironments/env",
+                    "configuration": {
+                        "ingress": { "external": true, "targetPort": 80 },
+                        "dapr": { "enabled": true, "appId": "service1", "appPort": 3000 }
+                    },
+                    "template": {
+                        "containers": [{
+                            "name": "service1",
+                            "image": "capps.azurecr.io/service1:v1",
+                            "volumeMounts": [{ "volumeName": "data", "mountPath": "/data" }]
+                        }],
+                        "volumes": [{ "name": "data", "storageType": "AzureFile", "storageName": "files" }]
+                    }
+                }
+            }),
+            json!({
+                "type": "Microsoft.App/containerAppsNotWorking",
+                "name": "service2",
+                "properties": {}
+            }),
+        ];
+
+        let output = get_apps(&resources);
+
+        let expected = vec![ContainerAppBluePrint {
+            configuration: Some(ConfigurationBluePrint {
+                ingress: Some(IngressBluePrint {
+                    external: Some(true),
+                    target_port: Some(80),
+                }),
+                dapr: Some(DaprBluePrint {
+                    app_id: Some("service1".to_string()),
+                    app_port: Some(3000),
+                    enabled: Some(true),
+                    app_protocol: None,
+                }),
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
+            }),
+            template: Some(TemplateBluePrint {
+                containers: Some(vec![ContainerBluePrint {
+                    name: "service1".to_string(),
+                    image: "capps.azurecr.io/service1:v1".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: Some(vec![VolumeMountBluePrint {
+                        volume_name: Some("data".to_string()),
+                        mount_path: Some("/data".to_string()),
+                        sub_path: None,
+                    }]),
+                }]),
+                scale: None,
+                volumes: Some(vec![VolumeBluePrint {
+                    name: "data".to_string(),
+                    storage_type: Some("AzureFile".to_string()),
+                    storage_name: Some("files".to_string()),
+                    secrets: None,
+                }]),
+            }),
+        }];
+
+        assert_eq!(Ok(expected), output);
+
+        let resources = vec![json!({ "type": "Microsoft.App/containerApps" })];
+        let output = get_apps(&resources);
+        assert_eq!(
+            Err("Container app properties need to be defined".to_string()),
+            output
+        );
+    }
+}
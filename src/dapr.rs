@@ -0,0 +1,365 @@
+use clap::Args;
+use log::warn;
+use serde::Deserialize;
+use std::fs;
+
+use crate::serializer::{
+    placement_configuration, ContainerAppConfiguration, DEFAULT_DAPR_VERSION, SIDECAR_IMAGE,
+};
+
+// The Dapr configuration file is mounted into the sidecars
+const CONFIG_PATH: &str = "/dapr/config.yaml";
+
+/***
+ * Dapr settings of the sidecars and the placement service, given on the CLI or in the `dapr` section of a settings file
+ */
+#[derive(Args, Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DaprSettings {
+    /// Dapr runtime version of the sidecars and the placement service (eg: 1.13.5), latest by default
+    #[arg(id = "dapr_version", long = "dapr-version")]
+    pub version: Option<String>,
+    /// Log level of the sidecars (eg: debug, info, warn, error)
+    #[arg(long = "dapr-log-level")]
+    pub log_level: Option<String>,
+    /// Port of the metrics of the sidecars
+    #[arg(long = "dapr-metrics-port")]
+    pub metrics_port: Option<u32>,
+    /// Port of the profiling of the sidecars, profiling is enabled when it is set
+    #[arg(long = "dapr-profile-port")]
+    pub profile_port: Option<u32>,
+    /// Max size of the request bodies in MB, given to `-max-body-size` as `<n>Mi` from Dapr 1.14 and for `latest`
+    #[arg(long = "dapr-http-max-request-size")]
+    pub http_max_request_size: Option<u32>,
+    /// Log the calls to the Dapr API
+    #[arg(long = "dapr-enable-api-logging", num_args = 0..=1, default_missing_value = "true")]
+    pub enable_api_logging: Option<bool>,
+    /// Dapr configuration file of the sidecars, relative to the compose file
+    #[arg(long = "dapr-config")]
+    pub config: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Settings {
+    dapr: Option<DaprSettings>,
+}
+
+impl DaprSettings {
+    /***
+     * Settings which are not set are taken from the fallback, eg: the CLI ones take precedence over the settings file
+     */
+    pub fn merge(self, fallback: DaprSettings) -> DaprSettings {
+        DaprSettings {
+            version: self.version.or(fallback.version),
+            log_level: self.log_level.or(fallback.log_level),
+            metrics_port: self.metrics_port.or(fallback.metrics_port),
+            profile_port: self.profile_port.or(fallback.profile_port),
+            http_max_request_size: self
+                .http_max_request_size
+                .or(fallback.http_max_request_size),
+            enable_api_logging: self.enable_api_logging.or(fallback.enable_api_logging),
+            config: self.config.or(fallback.config),
+        }
+    }
+}
+
+/***
+ * Dapr settings of a YAML settings file, eg: `dapr: { version: 1.13.5, logLevel: debug }`
+ */
+pub fn read_settings(path: &str) -> Result<DaprSettings, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let settings: Settings =
+        serde_yaml::from_str(&content).map_err(|e| format!("{}: {}", path, e))?;
+
+    Ok(settings.dapr.unwrap_or_default())
+}
+
+// Major and minor versions of a tag such as `1.13.5` or `v1.12.0-rc.1`, `latest` or `edge` have none
+fn parse_version(tag: &str) -> Option<(u32, u32)> {
+    let mut parts = tag.trim_start_matches('v').split(['.', '-']);
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+
+    Some((major, minor))
+}
+
+// Tags without a version are the newest runtime
+fn is_older(version: Option<(u32, u32)>, than: (u32, u32)) -> bool {
+    version.is_some_and(|version| version < than)
+}
+
+/***
+ * Flags of the sidecar for its runtime version, the app ones are given by the providers
+ */
+fn build_sidecar_command(
+    command: &[String],
+    settings: &DaprSettings,
+    version: Option<(u32, u32)>,
+) -> Vec<String> {
+    // Resources path and TLS app protocols come with Dapr 1.11
+    let is_legacy = is_older(version, (1, 11));
+    let mut result: Vec<String> = Vec::new();
+    let mut args = command.iter();
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), is_legacy) {
+            ("-resources-path", true) => result.push("-components-path".to_string()),
+            ("-app-protocol", true) => {
+                let protocol = args.next().map(String::as_str).unwrap_or("http");
+                let flags = match protocol {
+                    "https" => vec!["http", "-app-ssl"],
+                    "grpcs" => vec!["grpc", "-app-ssl"],
+                    "http" | "grpc" => vec![protocol],
+                    _ => {
+                        warn!(
+                            "App protocol {} is not supported before Dapr 1.11, http is used",
+                            protocol
+                        );
+                        vec!["http"]
+                    }
+                };
+                result.push(arg.clone());
+                result.extend(flags.iter().map(|flag| flag.to_string()));
+            }
+            _ => result.push(arg.clone()),
+        }
+    }
+
+    if let Some(log_level) = &settings.log_level {
+        result.extend(["-log-level".to_string(), log_level.clone()]);
+    }
+    if let Some(port) = settings.metrics_port {
+        result.extend(["-metrics-port".to_string(), port.to_string()]);
+    }
+    if let Some(port) = settings.profile_port {
+        result.extend([
+            "-enable-profiling".to_string(),
+            "-profile-port".to_string(),
+            port.to_string(),
+        ]);
+    }
+    // The request size flag is deprecated by the body size one since Dapr 1.14
+    if let Some(size) = settings.http_max_request_size {
+        match is_older(version, (1, 14)) {
+            true => result.extend(["-dapr-http-max-request-size".to_string(), size.to_string()]),
+            false => result.extend(["-max-body-size".to_string(), format!("{}Mi", size)]),
+        }
+    }
+    if settings.enable_api_logging == Some(true) {
+        match is_older(version, (1, 9)) {
+            true => warn!("API logging is not supported before Dapr 1.9, it is not enabled"),
+            false => result.push("-enable-api-logging".to_string()),
+        }
+    }
+    if settings.config.is_some() {
+        result.extend(["-config".to_string(), CONFIG_PATH.to_string()]);
+    }
+
+    result
+}
+
+/***
+ * Sidecars and the placement service are set to the Dapr version of the settings, with their runtime flags
+ */
+pub fn configure_dapr(
+    services: &[ContainerAppConfiguration],
+    settings: &DaprSettings,
+) -> Vec<ContainerAppConfiguration> {
+    let tag = settings.version.as_deref().unwrap_or(DEFAULT_DAPR_VERSION);
+    let version = parse_version(tag);
+    if settings.version.is_none() && services.iter().any(|service| service.is_dapr_sidecar) {
+        warn!(
+            "Dapr version is not pinned, {} images are used",
+            DEFAULT_DAPR_VERSION
+        );
+    }
+    // Compose resolves bind mounts from the folder of the compose file
+    let config_volume = settings.config.as_ref().map(|config| {
+        let source = match config.starts_with(['.', '/', '~', '$']) {
+            true => config.clone(),
+            false => format!("./{}", config),
+        };
+        format!("{}:{}:ro", source, CONFIG_PATH)
+    });
+
+    services
+        .iter()
+        .map(|service| match service.is_dapr_sidecar {
+            true => ContainerAppConfiguration {
+                image: Some(format!("{}:{}", SIDECAR_IMAGE, tag)),
+                command: service
+                    .command
+                    .as_ref()
+                    .map(|command| build_sidecar_command(command, settings, version)),
+                volumes: match (&service.volumes, &config_volume) {
+                    (Some(volumes), Some(config)) => {
+                        Some([volumes.clone(), vec![config.clone()]].concat())
+                    }
+                    (None, Some(config)) => Some(vec![config.clone()]),
+                    (volumes, None) => volumes.clone(),
+                },
+                ..service.clone()
+            },
+            false => service.clone(),
+        })
+        .chain([placement_configuration(tag)])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sidecar() -> ContainerAppConfiguration {
+        ContainerAppConfiguration {
+            image: Some("daprio/daprd:latest".to_string()),
+            build: None,
+            name: "myapp_dapr".to_string(),
+            depends_on: None,
+            networks: None,
+            network_mode: Some("service:myapp".to_string()),
+            environment: None,
+            ports: None,
+            command: Some(
+                [
+                    "./daprd",
+                    "-app-id",
+                    "myapp",
+                    "-app-port",
+                    "3000",
+                    "-placement-host-address",
+                    "placement:50006",
+                    "-app-protocol",
+                    "grpcs",
+                    "-resources-path",
+                    "/components",
+                ]
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect(),
+            ),
+            deploy: None,
+            env_file: None,
+            secrets: None,
+            entrypoint: None,
+            healthcheck: None,
+            volumes: Some(vec!["./components:/components:ro".to_string()]),
+            files: None,
+            working_dir: None,
+            profiles: None,
+            restart: None,
+            is_dapr_sidecar: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(Some((1, 13)), parse_version("1.13.5"));
+        assert_eq!(Some((1, 12)), parse_version("v1.12.0-rc.1"));
+        assert_eq!(None, parse_version("latest"));
+        assert_eq!(None, parse_version("edge"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let cli = DaprSettings {
+            log_level: Some("debug".to_string()),
+            ..DaprSettings::default()
+        };
+        let file = DaprSettings {
+            version: Some("1.13.5".to_string()),
+            log_level: Some("info".to_string()),
+            ..DaprSettings::default()
+        };
+
+        let output = cli.merge(file);
+
+        assert_eq!(Some("1.13.5".to_string()), output.version);
+        assert_eq!(Some("debug".to_string()), output.log_level);
+    }
+
+    #[test]
+    fn test_configure_dapr() {
+        let settings = DaprSettings {
+            version: Some("1.14.4".to_string()),
+            log_level: Some("debug".to_string()),
+            metrics_port: Some(9090),
+            profile_port: None,
+            http_max_request_size: Some(16),
+            enable_api_logging: Some(true),
+            config: Some("dapr/config.yaml".to_string()),
+        };
+
+        let output = configure_dapr(&[sidecar()], &settings);
+
+        assert_eq!(Some("daprio/daprd:1.14.4".to_string()), output[0].image);
+        assert_eq!(
+            Some(
+                [
+                    "-app-protocol",
+                    "grpcs",
+                    "-resources-path",
+                    "/components",
+                    "-log-level",
+                    "debug",
+                    "-metrics-port",
+                    "9090",
+                    "-max-body-size",
+                    "16Mi",
+                    "-enable-api-logging",
+                    "-config",
+                    "/dapr/config.yaml",
+                ]
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+            ),
+            output[0]
+                .command
+                .as_ref()
+                .map(|command| command[7..].to_vec())
+        );
+        assert_eq!(
+            Some(vec![
+                "./components:/components:ro".to_string(),
+                "./dapr/config.yaml:/dapr/config.yaml:ro".to_string(),
+            ]),
+            output[0].volumes
+        );
+        // The placement service runs the same version
+        assert_eq!("placement", output[1].name);
+        assert_eq!(Some("daprio/dapr:1.14.4".to_string()), output[1].image);
+    }
+
+    #[test]
+    fn test_configure_dapr_with_legacy_version() {
+        let settings = DaprSettings {
+            version: Some("1.10.9".to_string()),
+            http_max_request_size: Some(16),
+            ..DaprSettings::default()
+        };
+
+        let output = configure_dapr(&[sidecar()], &settings);
+
+        assert_eq!(
+            Some(
+                [
+                    "-app-protocol",
+                    "grpc",
+                    "-app-ssl",
+                    "-components-path",
+                    "/components",
+                    "-dapr-http-max-request-size",
+                    "16",
+                ]
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+            ),
+            output[0]
+                .command
+                .as_ref()
+                .map(|command| command[7..].to_vec())
+        );
+    }
+}
@@ -1,31 +1,57 @@
+pub mod azure;
+pub mod dapr;
+pub mod project;
 pub mod pulumi;
 pub mod serializer;
+pub mod terraform;
 
 use clap::{Parser, ValueEnum};
 
-use log::{error, info};
+use azure::Azure;
+use dapr::DaprSettings;
+use log::{error, info, warn};
 use pulumi::Pulumi;
-use serializer::{Language, Serializer};
-use std::{fs, path::Path};
+use serializer::{ContainerAppConfiguration, Language, Serializer};
+use std::{collections::BTreeMap, fs, path::Path};
+use terraform::Terraform;
 
 const FILENAME: &str = "docker-compose.yml";
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Convertor type (eg: pulumi, azure, terraform)
+    /// Convertor type (eg: pulumi, azure, terraform), detected when the input is a folder or inferred from the extension of a file other than JSON
     #[arg(value_enum)]
-    provider: Provider,
+    provider: Option<Provider>,
 
-    /// input file to convert
+    /// input file or project folder to convert
     #[arg(short, long)]
     input: String,
     // Output folder
     #[arg(short, long)]
     output: String,
+
+    /// Pulumi stack whose configuration (Pulumi.<stack>.yaml) is used to resolve values
+    #[arg(long)]
+    stack: Option<String>,
+
+    /// Terraform variable files (.tfvars), terraform.tfvars and *.auto.tfvars are loaded anyway
+    #[arg(long)]
+    var_file: Vec<String>,
+
+    /// Local folder whose sub folders are bind mounted instead of named volumes for the storages of the environment
+    #[arg(long)]
+    volumes_folder: Option<String>,
+
+    /// Settings file (YAML) whose `dapr` section is used for the options which are not given
+    #[arg(long)]
+    settings: Option<String>,
+
+    #[command(flatten)]
+    dapr: DaprSettings,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, ValueEnum)]
-enum Provider {
+pub enum Provider {
     /// Provider for Pulumi
     Pulumi,
     /// Provider for Azure
@@ -40,60 +66,250 @@ fn parse_language(filename: &str) -> Language {
     match language {
         Some("yml" | "yaml") => Language::Yaml,
         Some("ts") => Language::Typescript,
+        Some("js") => Language::Javascript,
+        Some("go") => Language::Go,
+        Some("py") => Language::Python,
+        Some("cs") => Language::CSharp,
         Some("bicep") => Language::Bicep,
         Some("json") => Language::Json,
+        Some("tf") => Language::Hcl,
         _ => Language::NotSupported,
     }
 }
 
+// Modules which cannot be read are skipped, the program may still declare its resources
+fn read_modules(paths: &[String]) -> Vec<String> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            fs::read_to_string(path)
+                .map_err(|e| warn!("Module {} cannot be read: {}", path, e))
+                .ok()
+        })
+        .collect()
+}
+
+// JSON files can be an ARM template, a Terraform plan or a Pulumi stack export, their provider is required
+fn infer_provider(language: Language) -> Option<Provider> {
+    match language {
+        Language::Yaml
+        | Language::Typescript
+        | Language::Javascript
+        | Language::Go
+        | Language::Python
+        | Language::CSharp => Some(Provider::Pulumi),
+        Language::Bicep => Some(Provider::Azure),
+        Language::Hcl => Some(Provider::Terraform),
+        Language::Json | Language::NotSupported => None,
+    }
+}
+
+fn write_configuration<T: Serializer>(
+    provider: &T,
+    services: &[ContainerAppConfiguration],
+    output: &str,
+    volumes_folder: Option<&str>,
+    dapr: &DaprSettings,
+) {
+    let path = format!("{}/{}", output, FILENAME);
+    let services = dapr::configure_dapr(services, dapr);
+    let services = match volumes_folder {
+        Some(folder) => serializer::bind_volumes_to_folder(&services, folder),
+        None => services,
+    };
+
+    match provider.serialize_value(&services) {
+        Ok(v) => {
+            if Path::new(&path).exists() {
+                let old_file = fs::read_to_string(Path::new(&path));
+                let old_file_path = format!("{}/{}", output, "docker-compose.old.yml");
+
+                match fs::write(old_file_path, old_file.unwrap()) {
+                    Ok(_r) => {
+                        info!("Previous compose file dumped to >> docker-compose.old.yml")
+                    }
+                    Err(e) => error!("{}", e),
+                };
+            }
+
+            fs::write(&path, v).unwrap();
+
+            // Secrets are only written to the env files of their services
+            for service in &services {
+                if let (Some(secrets), Some(env_files)) = (&service.secrets, &service.env_file) {
+                    let env_file_path = format!("{}/{}", output, env_files[0]);
+                    match fs::write(&env_file_path, format!("{}\n", secrets.join("\n"))) {
+                        Ok(_r) => {
+                            info!("Secrets of {} written to >> {}", service.name, env_files[0])
+                        }
+                        Err(e) => error!("{}", e),
+                    };
+                }
+            }
+
+            // Files such as Dapr components can be shared by several services
+            let files: BTreeMap<&String, &String> = services
+                .iter()
+                .flat_map(|service| service.files.iter().flatten())
+                .collect();
+            for (file, value) in files {
+                let file_path = Path::new(output).join(file);
+                let written = fs::create_dir_all(file_path.parent().unwrap())
+                    .and_then(|_| fs::write(&file_path, value));
+                match written {
+                    Ok(_r) => info!("File written to >> {}", file),
+                    Err(e) => error!("{}", e),
+                };
+            }
+
+            info!("Completed!")
+        }
+        Err(e) => error!("{}", e),
+    }
+}
+
 fn main() {
     simple_logger::init().unwrap();
     let args = Args::parse();
 
     info!("Starting...");
 
-    let file = fs::read_to_string(&args.input);
-    let path = format!("{}/{}", args.output, FILENAME);
+    let dapr = match &args.settings {
+        Some(path) => match dapr::read_settings(path) {
+            Ok(settings) => args.dapr.clone().merge(settings),
+            Err(e) => {
+                error!("{}", e);
+                return;
+            }
+        },
+        None => args.dapr.clone(),
+    };
+
+    let (provider, language, files) = if Path::new(&args.input).is_dir() {
+        match project::detect(&args.input, args.provider) {
+            Ok(project) => {
+                info!(
+                    "Detected {:?} project with {:?} language",
+                    project.provider, project.language
+                );
+                (project.provider, project.language, project.files)
+            }
+            Err(e) => {
+                error!("{}", e);
+                return;
+            }
+        }
+    } else {
+        let language = parse_language(&args.input);
+        let Some(provider) = args.provider.or_else(|| infer_provider(language)) else {
+            error!("Provider is required when the input is a file, eg: `pulumi`, `azure` or `terraform`");
+            return;
+        };
+        (provider, language, vec![args.input.clone()])
+    };
+    // Entry file is used to find related files such as imports or variable files
+    let entry = &files[0];
+
+    // Files are parsed on their own, the entry file is followed by the other files of the program
+    let file = files
+        .iter()
+        .map(fs::read_to_string)
+        .collect::<Result<Vec<String>, _>>()
+        .map(|mut contents| (contents.remove(0), contents));
 
     match file {
-        Ok(file) => {
-            let language = parse_language(&args.input);
-
-            match args.provider {
-                Provider::Pulumi => {
-                    let mut provider =
-                        Pulumi::new(language).expect("Language is not supported for this provider");
-
-                    let value = provider
-                        .deserialize_value(&file)
-                        .expect("Deserialiazed value is defined");
-
-                    match value.serialize_value(&value.resources.as_ref().unwrap()) {
-                        Ok(v) => {
-                            if Path::new(&path).exists() {
-                                let old_file = fs::read_to_string(Path::new(&path));
-                                let old_file_path =
-                                    format!("{}/{}", args.output, "docker-compose.old.yml");
-
-                                match fs::write(old_file_path, old_file.unwrap()) {
-                                    Ok(_r) => {
-                                        info!("Previous compose file dumped to >> docker-compose.old.yml")
-                                    }
-                                    Err(e) => error!("{}", e),
-                                };
-                            }
-
-                            fs::write(&path, v).unwrap();
-
-                            info!("Completed!")
-                        }
-                        Err(e) => error!("{}", e),
+        Ok((file, other_files)) => match provider {
+            Provider::Pulumi => {
+                let Some(mut provider) = Pulumi::new(language) else {
+                    error!("Language is not supported for this provider");
+                    return;
+                };
+
+                provider.config = pulumi::config::find_config(entry, args.stack.as_deref());
+                provider.modules = match language {
+                    Language::Typescript | Language::Javascript => {
+                        read_modules(&pulumi::js::find_modules(entry))
                     }
-                }
-                Provider::Azure => todo!(),
-                Provider::Terraform => todo!(),
+                    Language::Python => read_modules(&pulumi::python::find_modules(entry)),
+                    // Every file of a Go package or a C# project is part of the program
+                    _ => other_files,
+                };
+
+                let value = match provider.deserialize_value(&file) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        error!("{}", e);
+                        return;
+                    }
+                };
+
+                write_configuration(
+                    value,
+                    value.resources.as_ref().unwrap(),
+                    &args.output,
+                    args.volumes_folder.as_deref(),
+                    &dapr,
+                );
             }
-        }
+            Provider::Azure => {
+                let Some(mut provider) = Azure::new(language) else {
+                    error!("Language is not supported for this provider");
+                    return;
+                };
+
+                let value = match provider.deserialize_value(&file) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        error!("{}", e);
+                        return;
+                    }
+                };
+
+                write_configuration(
+                    value,
+                    value.resources.as_ref().unwrap(),
+                    &args.output,
+                    args.volumes_folder.as_deref(),
+                    &dapr,
+                );
+            }
+            Provider::Terraform => {
+                let Some(mut provider) = Terraform::new(language) else {
+                    error!("Language is not supported for this provider");
+                    return;
+                };
+
+                provider.files = other_files;
+                let variables = [terraform::find_var_files(entry), args.var_file]
+                    .concat()
+                    .iter()
+                    .map(|path| fs::read_to_string(path).map_err(|e| (path.clone(), e)))
+                    .collect::<Result<Vec<String>, _>>();
+                provider.variables = match variables {
+                    Ok(variables) => variables,
+                    Err((path, e)) => {
+                        error!("Variable file {} cannot be read: {}", path, e);
+                        return;
+                    }
+                };
+
+                let value = match provider.deserialize_value(&file) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        error!("{}", e);
+                        return;
+                    }
+                };
+
+                write_configuration(
+                    value,
+                    value.resources.as_ref().unwrap(),
+                    &args.output,
+                    args.volumes_folder.as_deref(),
+                    &dapr,
+                );
+            }
+        },
         Err(e) => error!("{}", e),
     }
 }
@@ -105,4 +321,21 @@ mod tests {
     fn it_works() {
         assert_eq!(4, 4);
     }
+
+    #[test]
+    fn test_infer_provider() {
+        use super::{infer_provider, Language, Provider};
+
+        assert_eq!(Some(Provider::Terraform), infer_provider(Language::Hcl));
+        assert_eq!(Some(Provider::Azure), infer_provider(Language::Bicep));
+        assert_eq!(Some(Provider::Pulumi), infer_provider(Language::Go));
+        assert_eq!(None, infer_provider(Language::Json));
+    }
+
+    #[test]
+    fn test_args() {
+        use clap::CommandFactory;
+
+        super::Args::command().debug_assert();
+    }
 }
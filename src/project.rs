@@ -0,0 +1,275 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_yaml::Value;
+
+use crate::serializer::Language;
+use crate::Provider;
+
+// Folders which never contain the program of a project
+const IGNORED_FOLDERS: [&str; 6] = [
+    "node_modules",
+    "target",
+    "bin",
+    "obj",
+    "venv",
+    "__pycache__",
+];
+// Depth of the folders searched from the input folder
+const MAX_DEPTH: usize = 3;
+
+/***
+ * IAC project found in a folder
+ */
+#[derive(Debug, PartialEq)]
+pub struct Project {
+    pub provider: Provider,
+    pub language: Language,
+    /// Files of the program, the entry file comes first
+    pub files: Vec<String>,
+}
+
+fn to_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn list_files(folder: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(folder) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some(extension))
+            .collect(),
+        Err(_) => vec![],
+    };
+    files.sort();
+
+    files
+}
+
+// Entry file comes first, the other files of the program follow
+fn with_entry(entry: &Path, files: Vec<PathBuf>) -> Vec<String> {
+    let mut output = vec![];
+    if entry.is_file() {
+        output.push(to_string(entry));
+    }
+    output.extend(
+        files
+            .iter()
+            .filter(|file| *file != entry)
+            .map(|file| to_string(file)),
+    );
+
+    output
+}
+
+// Runtime can be a name or an object, eg: `runtime: { name: nodejs, options: { typescript: true } }`
+fn get_runtime(project: &Value) -> Option<String> {
+    match project.get("runtime")? {
+        Value::String(name) => Some(name.to_string()),
+        runtime => runtime.get("name")?.as_str().map(|name| name.to_string()),
+    }
+}
+
+/***
+ * Pulumi program described by its `Pulumi.yaml`, `main` overrides the folder or the file of the program
+ */
+fn detect_pulumi(folder: &Path) -> Option<Project> {
+    let project_file = ["Pulumi.yaml", "Pulumi.yml"]
+        .iter()
+        .map(|name| folder.join(name))
+        .find(|path| path.is_file())?;
+    let project: Value = serde_yaml::from_str(&fs::read_to_string(&project_file).ok()?).ok()?;
+
+    let main = project
+        .get("main")
+        .and_then(|main| main.as_str())
+        .map(|main| folder.join(main));
+    let program_folder = match &main {
+        Some(main) if main.is_dir() => main.to_path_buf(),
+        _ => folder.to_path_buf(),
+    };
+    let main_file = main.filter(|main| main.is_file());
+    let entry = |names: &[&str]| {
+        main_file.clone().or_else(|| {
+            names
+                .iter()
+                .map(|name| program_folder.join(name))
+                .find(|path| path.is_file())
+        })
+    };
+
+    let (language, files) = match get_runtime(&project)?.as_str() {
+        "yaml" => (
+            Language::Yaml,
+            vec![to_string(&entry(&["Main.yaml"]).unwrap_or(project_file))],
+        ),
+        "nodejs" => {
+            let entry = entry(&["index.ts", "index.js"])?;
+            let language = match entry.extension().and_then(|e| e.to_str()) {
+                Some("ts") => Language::Typescript,
+                _ => Language::Javascript,
+            };
+            (language, vec![to_string(&entry)])
+        }
+        "python" => (Language::Python, vec![to_string(&entry(&["__main__.py"])?)]),
+        // Every file of the package is part of the program
+        "go" => {
+            let files = list_files(&program_folder, "go")
+                .into_iter()
+                .filter(|file| !to_string(file).ends_with("_test.go"))
+                .collect();
+            let entry = entry(&["main.go"]).unwrap_or_default();
+            (Language::Go, with_entry(&entry, files))
+        }
+        "dotnet" => {
+            let entry = entry(&["Program.cs"]).unwrap_or_default();
+            (
+                Language::CSharp,
+                with_entry(&entry, list_files(&program_folder, "cs")),
+            )
+        }
+        _ => return None,
+    };
+
+    if files.is_empty() {
+        return None;
+    }
+
+    Some(Project {
+        provider: Provider::Pulumi,
+        language,
+        files,
+    })
+}
+
+// Terraform merges every configuration file of a module
+fn detect_terraform(folder: &Path) -> Option<Project> {
+    let files = list_files(folder, "tf");
+    if files.is_empty() {
+        return None;
+    }
+
+    Some(Project {
+        provider: Provider::Terraform,
+        language: Language::Hcl,
+        files: files.iter().map(|file| to_string(file)).collect(),
+    })
+}
+
+fn detect_azure(folder: &Path) -> Option<Project> {
+    let template = folder.join("azuredeploy.json");
+    if template.is_file() {
+        return Some(Project {
+            provider: Provider::Azure,
+            language: Language::Json,
+            files: vec![to_string(&template)],
+        });
+    }
+
+    let main = folder.join("main.bicep");
+    let entry = if main.is_file() {
+        main
+    } else {
+        list_files(folder, "bicep").into_iter().next()?
+    };
+
+    Some(Project {
+        provider: Provider::Azure,
+        language: Language::Bicep,
+        files: vec![to_string(&entry)],
+    })
+}
+
+fn detect_in_folder(folder: &Path, provider: Option<Provider>) -> Option<Project> {
+    [Provider::Pulumi, Provider::Terraform, Provider::Azure]
+        .into_iter()
+        .filter(|candidate| provider.is_none() || provider == Some(*candidate))
+        .find_map(|candidate| match candidate {
+            Provider::Pulumi => detect_pulumi(folder),
+            Provider::Terraform => detect_terraform(folder),
+            Provider::Azure => detect_azure(folder),
+        })
+}
+
+/***
+ * Find the provider, the language and the files of the project in a folder or its sub folders
+ */
+pub fn detect(input: &str, provider: Option<Provider>) -> Result<Project, String> {
+    let mut folders = vec![PathBuf::from(input)];
+
+    // Breadth first, the closest project to the input folder is used
+    for _ in 0..=MAX_DEPTH {
+        for folder in &folders {
+            if let Some(project) = detect_in_folder(folder, provider) {
+                return Ok(project);
+            }
+        }
+
+        folders = folders
+            .iter()
+            .flat_map(|folder| {
+                let mut children: Vec<PathBuf> = match fs::read_dir(folder) {
+                    Ok(entries) => entries
+                        .flatten()
+                        .map(|entry| entry.path())
+                        .filter(|path| path.is_dir())
+                        .filter(|path| {
+                            let name = path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_default();
+                            !name.starts_with('.') && !IGNORED_FOLDERS.contains(&name.as_str())
+                        })
+                        .collect(),
+                    Err(_) => vec![],
+                };
+                children.sort();
+                children
+            })
+            .collect();
+    }
+
+    Err(format!("No IAC project found in {}", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let output = detect("examples/terraform/hcl", None);
+        let expected = Project {
+            provider: Provider::Terraform,
+            language: Language::Hcl,
+            files: vec!["examples/terraform/hcl/main.tf".to_string()],
+        };
+        assert_eq!(Ok(expected), output);
+
+        let output = detect("examples/azure", Some(Provider::Azure));
+        let expected = Project {
+            provider: Provider::Azure,
+            language: Language::Bicep,
+            files: vec!["examples/azure/bicep/main.bicep".to_string()],
+        };
+        assert_eq!(Ok(expected), output);
+
+        let output = detect("examples/pulumi/modules", None);
+        let expected = Project {
+            provider: Provider::Pulumi,
+            language: Language::Typescript,
+            files: vec!["examples/pulumi/modules/index.ts".to_string()],
+        };
+        assert_eq!(Ok(expected), output);
+
+        let output = detect("examples/pulumi/go", Some(Provider::Terraform));
+        assert_eq!(
+            Err("No IAC project found in examples/pulumi/go".to_string()),
+            output
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::warn;
+use serde_json::Value;
+
+const PROJECT_FILES: [&str; 2] = ["Pulumi.yaml", "Pulumi.yml"];
+
+/***
+ * Stack configuration, keys are namespaced by their package, eg: `project:appPort` or `azure-native:location`
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// Name of the project, namespace of the keys given without one
+    pub project: String,
+    /// Folder of the project, files read by the program are relative to it
+    pub folder: PathBuf,
+    pub values: HashMap<String, Value>,
+}
+
+impl Config {
+    fn key(&self, namespace: Option<&str>, key: &str) -> String {
+        if key.contains(':') {
+            key.to_string()
+        } else {
+            format!("{}:{}", namespace.unwrap_or(&self.project), key)
+        }
+    }
+
+    pub fn get(&self, namespace: Option<&str>, key: &str) -> Option<&Value> {
+        self.values.get(&self.key(namespace, key))
+    }
+
+    /***
+     * Declarations of the `config` or `configuration` sections of a project, stack values take precedence over defaults
+     */
+    pub fn declare(&mut self, declarations: &Value) {
+        let Some(declarations) = declarations.as_object() else {
+            return;
+        };
+
+        for (key, declaration) in declarations {
+            let key = self.key(None, key);
+            let declared_type = declaration.get("type").and_then(|t| t.as_str());
+
+            let default = match declaration {
+                Value::Object(declaration) => declaration
+                    .get("default")
+                    .or_else(|| declaration.get("value"))
+                    .cloned(),
+                value => Some(value.to_owned()),
+            };
+            if !self.values.contains_key(&key) {
+                if let Some(default) = default {
+                    self.values.insert(key.to_string(), default);
+                }
+            }
+
+            if let (Some(declared_type), Some(value)) = (declared_type, self.values.get_mut(&key)) {
+                *value = convert(value, declared_type);
+            }
+        }
+    }
+}
+
+/***
+ * Stack values are saved as strings, they are converted to the type they are read with
+ */
+pub fn convert(value: &Value, value_type: &str) -> Value {
+    let Value::String(text) = value else {
+        return value.to_owned();
+    };
+
+    let converted = match value_type.to_lowercase().as_str() {
+        "integer" | "int" => text.parse::<i64>().ok().map(Value::from),
+        "number" => text
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| text.parse::<f64>().map(Value::from))
+            .ok(),
+        "boolean" | "bool" => text.parse::<bool>().ok().map(Value::from),
+        "object" | "array" | "list" => serde_json::from_str(text).ok(),
+        _ => None,
+    };
+
+    converted.unwrap_or_else(|| value.to_owned())
+}
+
+// Pulumi.<stack>.yaml `config` section, encrypted values cannot be read
+fn read_stack_values(input: &str) -> Result<HashMap<String, Value>, String> {
+    let stack: Value = serde_yaml::from_str(input).map_err(|e| e.to_string())?;
+
+    let values = stack
+        .get("config")
+        .and_then(|config| config.as_object())
+        .map(|config| {
+            config
+                .iter()
+                .filter(|(key, value)| {
+                    let is_secure = value.get("secure").is_some();
+                    if is_secure {
+                        warn!("Secret {} cannot be decrypted, it is ignored", key);
+                    }
+                    !is_secure
+                })
+                .map(|(key, value)| (key.to_string(), value.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(values)
+}
+
+/***
+ * Configuration of a project and one of its stacks, a project without a stack only has its defaults
+ */
+pub fn parse_config(project: &str, stack: Option<&str>) -> Result<Config, String> {
+    let project: Value = serde_yaml::from_str(project).map_err(|e| e.to_string())?;
+
+    let mut config = Config {
+        project: project
+            .get("name")
+            .and_then(|name| name.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        folder: PathBuf::new(),
+        values: match stack {
+            Some(stack) => read_stack_values(stack)?,
+            None => HashMap::new(),
+        },
+    };
+
+    for section in ["config", "configuration"] {
+        if let Some(declarations) = project.get(section) {
+            config.declare(declarations);
+        }
+    }
+
+    Ok(config)
+}
+
+/***
+ * Configuration of the project containing the program, `Pulumi.yaml` is searched from its folder to the root
+ */
+pub fn find_config(input: &str, stack: Option<&str>) -> Config {
+    let project_file = Path::new(input)
+        .ancestors()
+        .skip(1)
+        .flat_map(|folder| PROJECT_FILES.iter().map(move |name| folder.join(name)))
+        .find(|path| path.is_file());
+
+    let Some(project_file) = project_file else {
+        if stack.is_some() {
+            warn!("No Pulumi project found for {}, stack is ignored", input);
+        }
+        return Config::default();
+    };
+
+    let stack_file = stack.map(|stack| {
+        project_file
+            .with_file_name(format!("Pulumi.{}.yaml", stack))
+            .to_string_lossy()
+            .to_string()
+    });
+    let stack = match &stack_file {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(stack) => Some(stack),
+            Err(e) => {
+                warn!("Stack file {} cannot be read: {}", path, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let config = fs::read_to_string(&project_file)
+        .map_err(|e| e.to_string())
+        .and_then(|project| parse_config(&project, stack.as_deref()));
+
+    match config {
+        Ok(config) => Config {
+            folder: project_file
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            ..config
+        },
+        Err(e) => {
+            warn!("Project file cannot be parsed: {}", e);
+            Config::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(json!(3000), convert(&json!("3000"), "integer"));
+        assert_eq!(json!(true), convert(&json!("true"), "Boolean"));
+        assert_eq!(json!({ "a": 1 }), convert(&json!("{\"a\":1}"), "object"));
+        assert_eq!(json!("abc"), convert(&json!("abc"), "integer"));
+        assert_eq!(json!(2), convert(&json!(2), "string"));
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let project = r#"
+        name: capps
+        runtime: nodejs
+        config:
+          appPort:
+            type: integer
+            default: 3000
+          replicas:
+            type: integer
+          azure-native:location: westeurope
+        "#;
+        let stack = r#"
+        config:
+          capps:replicas: "2"
+          capps:password:
+            secure: v1:abc
+        "#;
+
+        let output = parse_config(project, Some(stack));
+
+        let expected = Config {
+            project: "capps".to_string(),
+            folder: PathBuf::new(),
+            values: HashMap::from([
+                ("capps:appPort".to_string(), json!(3000)),
+                ("capps:replicas".to_string(), json!(2)),
+                ("azure-native:location".to_string(), json!("westeurope")),
+            ]),
+        };
+        assert_eq!(Ok(expected.clone()), output);
+        assert_eq!(Some(&json!(2)), expected.get(None, "replicas"));
+        assert_eq!(
+            Some(&json!("westeurope")),
+            expected.get(Some("azure-native"), "location")
+        );
+
+        // YAML programs declare their configuration with types and defaults
+        let project = r#"
+        name: capps
+        runtime: yaml
+        configuration:
+          appPort:
+            type: Number
+            default: 8000
+        "#;
+
+        let output = parse_config(project, None).unwrap();
+        assert_eq!(Some(&json!(8000)), output.get(None, "appPort"));
+    }
+}
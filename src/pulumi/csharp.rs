@@ -0,0 +1,1066 @@
+use std::fmt;
+
+use log::error;
+use serde_json::{Map, Value};
+
+use crate::pulumi;
+use crate::serializer::{
+    ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    // Interpolated string, holes are kept as written
+    Interpolated(String),
+    Number(String),
+    Symbol(String),
+}
+
+// `>>` is left out so nested generic arguments can be closed one by one
+const SYMBOLS: [&str; 15] = [
+    "??=", "=>", "??", "?.", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "::",
+];
+
+fn read_string(
+    chars: &[char],
+    position: &mut usize,
+    is_verbatim: bool,
+    is_interpolated: bool,
+) -> Result<String, String> {
+    let mut output = String::new();
+    // Braces inside holes must not end the string early
+    let mut depth = 0;
+    *position += 1;
+
+    while *position < chars.len() {
+        let c = chars[*position];
+        let next = chars.get(*position + 1).copied();
+        *position += 1;
+
+        match c {
+            '"' if depth == 0 && is_verbatim && next == Some('"') => {
+                *position += 1;
+                output.push('"');
+            }
+            '"' if depth == 0 => return Ok(output),
+            '\\' if !is_verbatim => {
+                let escaped = next.unwrap_or_default();
+                *position += 1;
+                output.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    c => c,
+                });
+            }
+            '{' if is_interpolated && next == Some('{') && depth == 0 => {
+                *position += 1;
+                output.push_str("{{");
+            }
+            '{' if is_interpolated => {
+                depth += 1;
+                output.push(c);
+            }
+            '}' if is_interpolated && depth > 0 => {
+                depth -= 1;
+                output.push(c);
+            }
+            '\n' if !is_verbatim => break,
+            c => output.push(c),
+        }
+    }
+
+    Err("Unterminated string literal".to_string())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while position < chars.len() {
+        let c = chars[position];
+        let next = chars.get(position + 1).copied().unwrap_or_default();
+
+        if c.is_whitespace() {
+            position += 1;
+        } else if c == '/' && next == '/' || c == '#' {
+            // Comments and preprocessor directives
+            while position < chars.len() && chars[position] != '\n' {
+                position += 1;
+            }
+        } else if c == '/' && next == '*' {
+            position += 2;
+            while position < chars.len() && !(chars[position - 1] == '*' && chars[position] == '/')
+            {
+                position += 1;
+            }
+            position += 1;
+        } else if c == '"' {
+            tokens.push(Token::String(read_string(
+                &chars,
+                &mut position,
+                false,
+                false,
+            )?));
+        } else if (c == '$' || c == '@') && (next == '"' || next == '$' || next == '@') {
+            let mut is_verbatim = false;
+            let mut is_interpolated = false;
+            while chars.get(position) != Some(&'"') {
+                is_verbatim |= chars[position] == '@';
+                is_interpolated |= chars[position] == '$';
+                position += 1;
+            }
+
+            let value = read_string(&chars, &mut position, is_verbatim, is_interpolated)?;
+            if is_interpolated {
+                tokens.push(Token::Interpolated(value));
+            } else {
+                tokens.push(Token::String(value));
+            }
+        } else if c == '\'' {
+            let start = position + 1;
+            position += 1;
+            while position < chars.len() && chars[position] != '\'' {
+                position += if chars[position] == '\\' { 2 } else { 1 };
+            }
+            position += 1;
+            tokens.push(Token::String(
+                chars[start..(position - 1).min(chars.len())]
+                    .iter()
+                    .collect(),
+            ));
+        } else if c.is_ascii_digit() {
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric()
+                    || chars[position] == '.'
+                    || chars[position] == '_')
+            {
+                position += 1;
+            }
+            tokens.push(Token::Number(chars[start..position].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' || (c == '@' && next.is_alphabetic()) {
+            // `@` only escapes keywords used as identifiers
+            if c == '@' {
+                position += 1;
+            }
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric() || chars[position] == '_')
+            {
+                position += 1;
+            }
+            tokens.push(Token::Ident(chars[start..position].iter().collect()));
+        } else {
+            let rest: String = chars[position..chars.len().min(position + 3)]
+                .iter()
+                .collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(*symbol))
+                .map(|symbol| symbol.to_string())
+                .unwrap_or_else(|| c.to_string());
+
+            position += symbol.chars().count();
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+    // `Name = value` in an object initializer
+    Member(String, Expression),
+    // `[key] = value` in an index initializer
+    Index(Expression, Expression),
+    // Item of a collection initializer
+    Item(Expression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    String(String),
+    Interpolated(String),
+    Number(String),
+    Ident(String),
+    // Lambdas are kept as a placeholder, their body is never evaluated
+    Lambda,
+    Member(Box<Expression>, String),
+    Call(Box<Expression>, Vec<Expression>),
+    Index(Box<Expression>, Box<Expression>),
+    // Type, constructor arguments and optional initializer
+    New(Option<String>, Vec<Expression>, Option<Vec<Element>>),
+    // Initializer without `new`, as found in a member value
+    Initializer(Vec<Element>),
+    Unary(String, Box<Expression>),
+    Binary(Box<Expression>, String, Box<Expression>),
+    Conditional(Box<Expression>, Box<Expression>, Box<Expression>),
+}
+
+fn join<T: fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Element::Member(name, value) => write!(f, "{} = {}", name, value),
+            Element::Index(key, value) => write!(f, "[{}] = {}", key, value),
+            Element::Item(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::String(value) => write!(f, "{:?}", value),
+            Expression::Interpolated(value) => write!(f, "${:?}", value),
+            Expression::Number(value) | Expression::Ident(value) => write!(f, "{}", value),
+            Expression::Lambda => write!(f, "() => {{...}}"),
+            Expression::Member(value, member) => write!(f, "{}.{}", value, member),
+            Expression::Call(callee, arguments) => write!(f, "{}({})", callee, join(arguments)),
+            Expression::Index(value, index) => write!(f, "{}[{}]", value, index),
+            Expression::New(type_name, arguments, initializer) => {
+                write!(f, "new {}", type_name.as_deref().unwrap_or_default())?;
+                if !arguments.is_empty() || initializer.is_none() {
+                    write!(f, "({})", join(arguments))?;
+                }
+                match initializer {
+                    Some(elements) => write!(f, " {{ {} }}", join(elements)),
+                    None => Ok(()),
+                }
+            }
+            Expression::Initializer(elements) => write!(f, "{{ {} }}", join(elements)),
+            Expression::Unary(operator, value) => write!(f, "{}{}", operator, value),
+            Expression::Binary(left, operator, right) => {
+                write!(f, "{} {} {}", left, operator, right)
+            }
+            Expression::Conditional(condition, value, alternative) => {
+                write!(f, "{} ? {} : {}", condition, value, alternative)
+            }
+        }
+    }
+}
+
+fn precedence(operator: &str) -> u8 {
+    match operator {
+        "??" => 1,
+        "||" => 2,
+        "&&" => 3,
+        "==" | "!=" => 4,
+        "<" | "<=" | ">" | ">=" => 5,
+        "+" | "-" => 6,
+        "*" | "/" | "%" => 7,
+        _ => 0,
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token], position: usize) -> Parser<'a> {
+        Parser { tokens, position }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if s == symbol)
+    }
+
+    fn is_symbol_at(&self, position: usize, symbol: &str) -> bool {
+        matches!(self.tokens.get(position), Some(Token::Symbol(s)) if s == symbol)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == symbol => Ok(()),
+            token => Err(format!("Expected `{}`, found {:?}", symbol, token)),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            token => Err(format!("Expected identifier, found {:?}", token)),
+        }
+    }
+
+    // Position of the bracket closing the one at the given position
+    fn find_closing(&self, position: usize) -> Option<usize> {
+        let mut depth = 0;
+
+        for (index, token) in self.tokens.iter().enumerate().skip(position) {
+            match token {
+                Token::Symbol(s) if ["(", "[", "{"].contains(&s.as_str()) => depth += 1,
+                Token::Symbol(s) if [")", "]", "}"].contains(&s.as_str()) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn is_lambda(&self) -> bool {
+        match self.peek() {
+            Some(Token::Ident(_)) => self.is_symbol_at(self.position + 1, "=>"),
+            Some(Token::Symbol(s)) if s == "(" => self
+                .find_closing(self.position)
+                .is_some_and(|closing| self.is_symbol_at(closing + 1, "=>")),
+            _ => false,
+        }
+    }
+
+    fn skip_lambda(&mut self) -> Result<Expression, String> {
+        if self.is_symbol("(") {
+            self.position = self.find_closing(self.position).unwrap_or(self.position);
+        }
+        self.position += 1;
+        self.expect_symbol("=>")?;
+
+        if self.is_symbol("{") {
+            match self.find_closing(self.position) {
+                Some(closing) => self.position = closing + 1,
+                None => return Err("Unterminated lambda body".to_string()),
+            }
+        } else {
+            self.parse_expression()?;
+        }
+
+        Ok(Expression::Lambda)
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == "async") {
+            self.position += 1;
+        }
+        if self.is_lambda() {
+            return self.skip_lambda();
+        }
+
+        let condition = self.parse_binary(1)?;
+        if !self.is_symbol("?") {
+            return Ok(condition);
+        }
+
+        self.position += 1;
+        let value = self.parse_expression()?;
+        self.expect_symbol(":")?;
+        let alternative = self.parse_expression()?;
+
+        Ok(Expression::Conditional(
+            Box::new(condition),
+            Box::new(value),
+            Box::new(alternative),
+        ))
+    }
+
+    fn parse_binary(&mut self, minimum: u8) -> Result<Expression, String> {
+        let mut left = self.parse_unary()?;
+
+        while let Some(Token::Symbol(operator)) = self.peek().cloned() {
+            let current = precedence(&operator);
+            if current == 0 || current < minimum {
+                break;
+            }
+            self.position += 1;
+            let right = self.parse_binary(current + 1)?;
+            left = Expression::Binary(Box::new(left), operator, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        let operator = match self.peek() {
+            Some(Token::Symbol(s)) if ["!", "-", "+", "~"].contains(&s.as_str()) => s.to_string(),
+            Some(Token::Ident(name)) if name == "await" => format!("{} ", name),
+            _ => {
+                let primary = self.parse_primary()?;
+                return self.parse_postfix(primary);
+            }
+        };
+
+        self.position += 1;
+        Ok(Expression::Unary(operator, Box::new(self.parse_unary()?)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        match self.peek().cloned() {
+            Some(Token::String(value)) => {
+                self.position += 1;
+                Ok(Expression::String(value))
+            }
+            Some(Token::Interpolated(value)) => {
+                self.position += 1;
+                Ok(Expression::Interpolated(value))
+            }
+            Some(Token::Number(value)) => {
+                self.position += 1;
+                Ok(Expression::Number(value))
+            }
+            Some(Token::Ident(name)) if name == "new" => {
+                self.position += 1;
+                self.parse_new()
+            }
+            Some(Token::Ident(name)) => {
+                self.position += 1;
+                Ok(Expression::Ident(name))
+            }
+            Some(Token::Symbol(s)) if s == "(" => {
+                self.position += 1;
+                let expression = self.parse_expression()?;
+                self.expect_symbol(")")?;
+                Ok(expression)
+            }
+            Some(Token::Symbol(s)) if s == "{" => {
+                Ok(Expression::Initializer(self.parse_initializer()?))
+            }
+            token => Err(format!("Unexpected token {:?}", token)),
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<String, String> {
+        let mut name = self.expect_ident()?;
+
+        loop {
+            if self.is_symbol(".") || self.is_symbol("::") {
+                self.position += 1;
+                name = format!("{}.{}", name, self.expect_ident()?);
+            } else if self.is_symbol("<") {
+                self.position += 1;
+                let mut arguments = vec![self.parse_type()?];
+                while self.is_symbol(",") {
+                    self.position += 1;
+                    arguments.push(self.parse_type()?);
+                }
+                self.expect_symbol(">")?;
+                name = format!("{}<{}>", name, arguments.join(", "));
+            } else if self.is_symbol("?") {
+                self.position += 1;
+                name.push('?');
+            } else if self.is_symbol("[") && self.is_symbol_at(self.position + 1, "]") {
+                self.position += 2;
+                name.push_str("[]");
+            } else {
+                return Ok(name);
+            }
+        }
+    }
+
+    fn parse_new(&mut self) -> Result<Expression, String> {
+        // Implicitly typed array, eg: `new[] { ... }`
+        let type_name = if self.is_symbol("[") {
+            self.expect_symbol("[")?;
+            self.expect_symbol("]")?;
+            Some("[]".to_string())
+        } else if matches!(self.peek(), Some(Token::Ident(_))) {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        let arguments = if self.is_symbol("(") {
+            self.parse_arguments()?
+        } else {
+            vec![]
+        };
+
+        let initializer = if self.is_symbol("{") {
+            Some(self.parse_initializer()?)
+        } else {
+            None
+        };
+
+        Ok(Expression::New(type_name, arguments, initializer))
+    }
+
+    fn parse_initializer(&mut self) -> Result<Vec<Element>, String> {
+        self.expect_symbol("{")?;
+        let mut elements = vec![];
+
+        while !self.is_symbol("}") {
+            let is_member = matches!(self.peek(), Some(Token::Ident(_)))
+                && self.is_symbol_at(self.position + 1, "=");
+
+            if is_member {
+                let name = self.expect_ident()?;
+                self.position += 1;
+                elements.push(Element::Member(name, self.parse_expression()?));
+            } else if self.is_symbol("[") {
+                self.position += 1;
+                let key = self.parse_expression()?;
+                self.expect_symbol("]")?;
+                self.expect_symbol("=")?;
+                elements.push(Element::Index(key, self.parse_expression()?));
+            } else {
+                elements.push(Element::Item(self.parse_expression()?));
+            }
+
+            if !self.is_symbol("}") {
+                self.expect_symbol(",")?;
+            }
+        }
+        self.position += 1;
+
+        Ok(elements)
+    }
+
+    fn parse_postfix(&mut self, mut expression: Expression) -> Result<Expression, String> {
+        loop {
+            if self.is_symbol(".") || self.is_symbol("?.") {
+                self.position += 1;
+                expression = Expression::Member(Box::new(expression), self.expect_ident()?);
+            } else if self.is_symbol("(") {
+                expression = Expression::Call(Box::new(expression), self.parse_arguments()?);
+            } else if self.is_symbol("[") {
+                self.position += 1;
+                let index = self.parse_expression()?;
+                self.expect_symbol("]")?;
+                expression = Expression::Index(Box::new(expression), Box::new(index));
+            } else if self.is_symbol("!") && !self.is_symbol_at(self.position + 1, "=") {
+                // Null forgiving operator
+                self.position += 1;
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<Expression>, String> {
+        self.expect_symbol("(")?;
+        let mut arguments = vec![];
+
+        while !self.is_symbol(")") {
+            // Named arguments are matched by position, their name is not needed
+            if matches!(self.peek(), Some(Token::Ident(_)))
+                && self.is_symbol_at(self.position + 1, ":")
+            {
+                self.position += 2;
+            }
+            arguments.push(self.parse_expression()?);
+
+            if !self.is_symbol(")") {
+                self.expect_symbol(",")?;
+            }
+        }
+        self.position += 1;
+
+        Ok(arguments)
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Null => "".to_string(),
+        v => v.to_string(),
+    }
+}
+
+// C# properties are PascalCase when blueprints are camelCase
+fn to_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Holes are kept as references, `{{` and `}}` are escapes
+fn format_interpolated(value: &str) -> String {
+    let mut output = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let hole: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let hole = hole.split([':', ',']).next().unwrap_or_default().trim();
+                match hole.rsplit_once('.') {
+                    Some((object, member)) => {
+                        output.push_str(&format!("${{{}.{}}}", object, to_camel_case(member)))
+                    }
+                    None => output.push_str(&format!("${{{}}}", hole)),
+                }
+            }
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn format(arguments: &[Value]) -> Option<Value> {
+    let mut output = value_to_string(arguments.first()?);
+    for (index, argument) in arguments.iter().skip(1).enumerate() {
+        output = output.replace(&format!("{{{}}}", index), &value_to_string(argument));
+    }
+    Some(Value::String(output))
+}
+
+/***
+ * Pulumi SDK and BCL functions, None when it cannot be resolved locally
+ */
+fn call_function(name: &str, arguments: &[Value]) -> Option<Value> {
+    match name {
+        "Output.Format" | "Output.Create" | "Output.CreateSecret" | "Input.Create" => {
+            arguments.first().cloned()
+        }
+        "string.Format" | "String.Format" => format(arguments),
+        "string.Concat" | "String.Concat" => Some(Value::String(
+            arguments.iter().map(value_to_string).collect(),
+        )),
+        _ => None,
+    }
+}
+
+fn is_collection(type_name: &str) -> bool {
+    [
+        "[]",
+        "InputList",
+        "List<",
+        "Array",
+        "IEnumerable",
+        "ICollection",
+    ]
+    .iter()
+    .any(|collection| type_name.contains(collection))
+}
+
+fn initializer_to_value(type_name: Option<&str>, elements: &[Element]) -> Value {
+    let is_object = !elements.is_empty()
+        && elements
+            .iter()
+            .all(|element| matches!(element, Element::Member(..) | Element::Index(..)));
+
+    if is_object {
+        let mut map = Map::new();
+        for element in elements {
+            match element {
+                Element::Member(name, value) => {
+                    map.insert(to_camel_case(name), to_value(value));
+                }
+                Element::Index(key, value) => {
+                    map.insert(value_to_string(&to_value(key)), to_value(value));
+                }
+                Element::Item(_) => {}
+            }
+        }
+        return Value::Object(map);
+    }
+
+    if elements.is_empty() && !type_name.is_some_and(is_collection) {
+        return Value::Object(Map::new());
+    }
+
+    Value::Array(
+        elements
+            .iter()
+            .map(|element| match element {
+                Element::Item(value) => to_value(value),
+                element => Value::String(element.to_string()),
+            })
+            .collect(),
+    )
+}
+
+fn to_value(expression: &Expression) -> Value {
+    match expression {
+        Expression::String(value) => Value::String(value.to_string()),
+        Expression::Interpolated(value) => Value::String(format_interpolated(value)),
+        Expression::Number(value) => {
+            // Literal suffixes such as `10L` or `0.5m`
+            let value = value
+                .trim_end_matches(|c: char| c.is_alphabetic())
+                .replace('_', "");
+            match value.parse::<i64>() {
+                Ok(number) => Value::from(number),
+                Err(_) => value
+                    .parse::<f64>()
+                    .map(Value::from)
+                    .unwrap_or(Value::String(value)),
+            }
+        }
+        Expression::Ident(name) if name == "true" => Value::Bool(true),
+        Expression::Ident(name) if name == "false" => Value::Bool(false),
+        Expression::Ident(name) if name == "null" => Value::Null,
+        // Output of another resource, kept as a reference like other languages
+        Expression::Member(object, member) if matches!(**object, Expression::Ident(_)) => {
+            Value::String(format!("${{{}.{}}}", object, to_camel_case(member)))
+        }
+        Expression::Call(callee, arguments) => {
+            let values: Vec<Value> = arguments.iter().map(to_value).collect();
+            call_function(&callee.to_string(), &values)
+                .unwrap_or_else(|| Value::String(expression.to_string()))
+        }
+        Expression::New(type_name, _, Some(elements)) => {
+            initializer_to_value(type_name.as_deref(), elements)
+        }
+        Expression::Initializer(elements) => initializer_to_value(None, elements),
+        Expression::Binary(left, operator, right) if operator == "+" => {
+            match (to_value(left), to_value(right)) {
+                (Value::String(left), right) => Value::String(left + &value_to_string(&right)),
+                (left, Value::String(right)) => Value::String(value_to_string(&left) + &right),
+                _ => Value::String(expression.to_string()),
+            }
+        }
+        expression => Value::String(expression.to_string()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ResourceCall {
+    variable: Option<String>,
+    arguments: Vec<Expression>,
+}
+
+// Target of `var name = new ...`, `Type name = new ...` or `name = new ...`
+fn get_assigned_variable(tokens: &[Token], position: usize) -> Option<String> {
+    let index = position.checked_sub(2)?;
+
+    match (&tokens[index], &tokens[index + 1]) {
+        (Token::Ident(name), Token::Symbol(s)) if s == "=" => {
+            // Member of an object initializer
+            let is_member =
+                index > 0 && matches!(&tokens[index - 1], Token::Symbol(s) if s == "," || s == "(");
+            (!is_member).then(|| name.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn find_resources(tokens: &[Token], class: &str) -> Result<Vec<ResourceCall>, String> {
+    let mut resources = vec![];
+
+    for (position, token) in tokens.iter().enumerate() {
+        if *token != Token::Ident("new".to_string()) {
+            continue;
+        }
+
+        // Type can be qualified, eg: `new AzureNative.App.ContainerApp(...)`
+        let mut index = position + 1;
+        while matches!(tokens.get(index + 1), Some(Token::Symbol(s)) if s == ".") {
+            index += 2;
+        }
+        let is_call = tokens.get(index) == Some(&Token::Ident(class.to_string()))
+            && tokens.get(index + 1) == Some(&Token::Symbol("(".to_string()));
+        if !is_call {
+            continue;
+        }
+
+        let mut parser = Parser::new(tokens, index + 1);
+        resources.push(ResourceCall {
+            variable: get_assigned_variable(tokens, position),
+            arguments: parser.parse_arguments()?,
+        });
+    }
+
+    Ok(resources)
+}
+
+fn get_images(tokens: &[Token]) -> Result<Vec<ContainerImageBluePrint>, String> {
+    // `Pulumi.Docker.Image` and `Pulumi.DockerBuild.Image`
+    let images = find_resources(tokens, "Image")?
+        .into_iter()
+        .filter_map(|resource| {
+            // Images cannot be referenced without a variable
+            let variable = resource.variable?;
+            let properties = to_value(resource.arguments.get(1)?);
+
+            pulumi::image::parse_image(Some(variable), &properties)
+        })
+        .collect();
+
+    Ok(images)
+}
+
+fn get_apps(tokens: &[Token]) -> Result<Vec<ContainerAppBluePrint>, String> {
+    [
+        find_resources(tokens, "ContainerApp")?,
+        find_resources(tokens, "Job")?,
+    ]
+    .iter()
+    .flatten()
+    .map(|resource| {
+        let properties = resource
+            .arguments
+            .get(1)
+            .ok_or("Container app arguments need to be defined")?;
+
+        serde_json::from_value(to_value(properties)).map_err(|e| e.to_string())
+    })
+    .collect()
+}
+
+fn get_components(tokens: &[Token]) -> Result<Vec<DaprComponentBluePrint>, String> {
+    let components = find_resources(tokens, "DaprComponent")?
+        .iter()
+        .filter_map(|resource| {
+            let name = to_value(resource.arguments.first()?);
+            let properties = to_value(resource.arguments.get(1)?);
+
+            pulumi::parse_dapr_component(name.as_str().map(|name| name.to_string()), properties)
+        })
+        .collect();
+
+    Ok(components)
+}
+
+pub fn deserialize(
+    input: &str,
+    modules: &[String],
+) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let mut images = vec![];
+    let mut apps = vec![];
+    let mut components = vec![];
+    // Each file is parsed on its own, resources of every file are built together
+    for module in [input]
+        .into_iter()
+        .chain(modules.iter().map(|module| module.as_str()))
+    {
+        let tokens = tokenize(module).map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+
+        images.append(&mut get_images(&tokens)?);
+        apps.append(&mut get_apps(&tokens)?);
+        components.append(&mut get_components(&tokens)?);
+    }
+
+    let services = pulumi::build_configuration(apps, images, components);
+
+    match services {
+        Some(val) => Ok(val),
+        None => Err("No container to deserialize".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::serializer::{
+        BuildContextBluePrint, ConfigurationBluePrint, ContainerBluePrint, DaprBluePrint,
+        IngressBluePrint, TemplateBluePrint,
+    };
+
+    use super::*;
+
+    fn parse_expression(input: &str) -> Result<Expression, String> {
+        let tokens = tokenize(input)?;
+        Parser::new(&tokens, 0).parse_expression()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let output = tokenize(r#"var app = new App.ContainerApp("app", null); // comment"#);
+        let expected = vec![
+            Token::Ident("var".to_string()),
+            Token::Ident("app".to_string()),
+            Token::Symbol("=".to_string()),
+            Token::Ident("new".to_string()),
+            Token::Ident("App".to_string()),
+            Token::Symbol(".".to_string()),
+            Token::Ident("ContainerApp".to_string()),
+            Token::Symbol("(".to_string()),
+            Token::String("app".to_string()),
+            Token::Symbol(",".to_string()),
+            Token::Ident("null".to_string()),
+            Token::Symbol(")".to_string()),
+            Token::Symbol(";".to_string()),
+        ];
+        assert_eq!(Ok(expected), output);
+
+        let output = tokenize(r#"$"{registry.LoginServer}/app:{{v1}}" @"C:\""path""""#);
+        let expected = vec![
+            Token::Interpolated("{registry.LoginServer}/app:{{v1}}".to_string()),
+            Token::String("C:\\\"path\"".to_string()),
+        ];
+        assert_eq!(Ok(expected), output);
+
+        let output = tokenize(r#""unterminated"#);
+        assert_eq!(Err("Unterminated string literal".to_string()), output);
+    }
+
+    #[test]
+    fn test_to_value() {
+        let expression = parse_expression(
+            "new IngressArgs { External = true, TargetPort = 80, Transport = IngressTransportMethod.Auto }",
+        )
+        .unwrap();
+        assert_eq!(
+            json!({ "external": true, "targetPort": 80, "transport": "${IngressTransportMethod.auto}" }),
+            to_value(&expression)
+        );
+
+        let expression = parse_expression(
+            r#"new TemplateArgs
+            {
+                Containers =
+                {
+                    new ContainerArgs { Name = "myapp", Image = image.ImageName },
+                },
+                Scale = new InputList<ScaleRuleArgs>(),
+                Volumes = new InputList<VolumeArgs> { },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json!({
+                "containers": [{ "name": "myapp", "image": "${image.imageName}" }],
+                "scale": "new InputList<ScaleRuleArgs>()",
+                "volumes": []
+            }),
+            to_value(&expression)
+        );
+
+        let expression =
+            parse_expression(r#"Output.Format($"{registry.LoginServer}/node-app:{tag}")"#).unwrap();
+        assert_eq!(
+            json!("${registry.loginServer}/node-app:${tag}"),
+            to_value(&expression)
+        );
+
+        let expression =
+            parse_expression("registry.LoginServer.Apply(server => $\"{server}/app\")").unwrap();
+        assert_eq!(
+            json!("registry.LoginServer.Apply(() => {...})"),
+            to_value(&expression)
+        );
+    }
+
+    #[test]
+    fn test_get_images() {
+        let data = r#"
+        var image = new Docker.Image("node-app", new Docker.ImageArgs
+        {
+            ImageName = Output.Format($"{registry.LoginServer}/node-app:v1.0.0"),
+            Build = new Docker.Inputs.DockerBuildArgs { Context = "./node-app" },
+        });
+        var remote = new Docker.Image("remote", new Docker.ImageArgs { ImageName = "nginx" });
+        "#;
+
+        let output = get_images(&tokenize(data).unwrap());
+
+        let expected = vec![ContainerImageBluePrint {
+            name: Some("${registry.loginServer}/node-app:v1.0.0".to_string()),
+            build: BuildContextBluePrint {
+                context: "./node-app".to_string(),
+                ..Default::default()
+            },
+            reference_name: Some("image".to_string()),
+        }];
+
+        assert_eq!(Ok(expected), output);
+    }
+
+    #[test]
+    fn test_get_apps() {
+        let data = r#"
+        var containerApp = new ContainerApp("app", new ContainerAppArgs
+        {
+            ResourceGroupName = resourceGroup.Name,
+            ManagedEnvironmentId = managedEnv.Id,
+            Configuration = new ConfigurationArgs
+            {
+                Dapr = new DaprArgs { Enabled = true, AppId = "myapp", AppPort = 3000 },
+                Ingress = new IngressArgs
+                {
+                    External = true,
+                    TargetPort = 80,
+                },
+            },
+            Template = new TemplateArgs
+            {
+                Containers =
+                {
+                    new ContainerArgs
+                    {
+                        Name = "myapp",
+                        Image = image.ImageName,
+                    },
+                },
+            },
+        });
+        "#;
+
+        let output = get_apps(&tokenize(data).unwrap());
+
+        let expected = vec![ContainerAppBluePrint {
+            configuration: Some(ConfigurationBluePrint {
+                ingress: Some(IngressBluePrint {
+                    external: Some(true),
+                    target_port: Some(80),
+                }),
+                dapr: Some(DaprBluePrint {
+                    app_id: Some("myapp".to_string()),
+                    app_port: Some(3000),
+                    enabled: Some(true),
+                    app_protocol: None,
+                }),
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
+            }),
+            template: Some(TemplateBluePrint {
+                containers: Some(vec![ContainerBluePrint {
+                    name: "myapp".to_string(),
+                    image: "${image.imageName}".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
+                }]),
+                scale: None,
+                volumes: None,
+            }),
+        }];
+
+        assert_eq!(Ok(expected), output);
+
+        let output = get_apps(&tokenize(r#"new ContainerApp("app")"#).unwrap());
+        assert_eq!(
+            Err("Container app arguments need to be defined".to_string()),
+            output
+        );
+    }
+}
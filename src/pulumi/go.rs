@@ -1,13 +1,839 @@
-use ccrate::pulumi;
+use std::fmt;
 
-pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String> {
-    //let images = ;
-    let apps = get_apps(&input);
+use log::error;
+use serde_json::{Map, Value};
 
-    let services = pulumi::build_configuration(apps, images);
+use crate::pulumi;
+use crate::serializer::{
+    ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Number(String),
+    Symbol(String),
+}
+
+const SYMBOLS: [&str; 15] = [
+    "...", ":=", "==", "!=", "<=", ">=", "&&", "||", "<-", "++", "--", "+=", "-=", "<<", ">>",
+];
+
+fn read_string(chars: &[char], position: &mut usize) -> Result<String, String> {
+    let quote = chars[*position];
+    let mut output = String::new();
+    *position += 1;
+
+    while *position < chars.len() {
+        let c = chars[*position];
+        *position += 1;
+
+        if c == quote {
+            return Ok(output);
+        }
+        // Raw strings have no escape sequences
+        if c == '\\' && quote != '`' {
+            let escaped = chars.get(*position).copied().unwrap_or_default();
+            *position += 1;
+            output.push(match escaped {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                c => c,
+            });
+        } else {
+            output.push(c);
+        }
+    }
+
+    Err("Unterminated string literal".to_string())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while position < chars.len() {
+        let c = chars[position];
+        let next = chars.get(position + 1).copied().unwrap_or_default();
+
+        if c.is_whitespace() {
+            position += 1;
+        } else if c == '/' && next == '/' {
+            while position < chars.len() && chars[position] != '\n' {
+                position += 1;
+            }
+        } else if c == '/' && next == '*' {
+            position += 2;
+            while position < chars.len() && !(chars[position - 1] == '*' && chars[position] == '/')
+            {
+                position += 1;
+            }
+            position += 1;
+        } else if c == '"' || c == '`' || c == '\'' {
+            tokens.push(Token::String(read_string(&chars, &mut position)?));
+        } else if c.is_ascii_digit() {
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric()
+                    || chars[position] == '.'
+                    || chars[position] == '_')
+            {
+                position += 1;
+            }
+            tokens.push(Token::Number(chars[start..position].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric() || chars[position] == '_')
+            {
+                position += 1;
+            }
+            tokens.push(Token::Ident(chars[start..position].iter().collect()));
+        } else {
+            let rest: String = chars[position..chars.len().min(position + 3)]
+                .iter()
+                .collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(*symbol))
+                .map(|symbol| symbol.to_string())
+                .unwrap_or_else(|| c.to_string());
+
+            position += symbol.chars().count();
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    String(String),
+    Number(String),
+    Ident(String),
+    // Slice, map or pointer type written in front of a composite literal
+    Type(String),
+    // Function literals are kept as a placeholder, their body is never evaluated
+    Function,
+    Selector(Box<Expression>, String),
+    Call(Box<Expression>, Vec<Expression>),
+    Index(Box<Expression>, Box<Expression>),
+    Composite(
+        Option<Box<Expression>>,
+        Vec<(Option<Expression>, Expression)>,
+    ),
+    Unary(String, Box<Expression>),
+    Binary(Box<Expression>, String, Box<Expression>),
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::String(value) => write!(f, "{:?}", value),
+            Expression::Number(value) | Expression::Ident(value) | Expression::Type(value) => {
+                write!(f, "{}", value)
+            }
+            Expression::Function => write!(f, "func() {{...}}"),
+            Expression::Selector(expression, field) => write!(f, "{}.{}", expression, field),
+            Expression::Call(callee, arguments) => {
+                let arguments: Vec<String> = arguments.iter().map(|a| a.to_string()).collect();
+                write!(f, "{}({})", callee, arguments.join(", "))
+            }
+            Expression::Index(expression, index) => write!(f, "{}[{}]", expression, index),
+            Expression::Composite(literal_type, elements) => {
+                let elements: Vec<String> = elements
+                    .iter()
+                    .map(|(key, value)| match key {
+                        Some(key) => format!("{}: {}", key, value),
+                        None => value.to_string(),
+                    })
+                    .collect();
+                let literal_type = literal_type
+                    .as_ref()
+                    .map(|t| t.to_string())
+                    .unwrap_or_default();
+                write!(f, "{}{{{}}}", literal_type, elements.join(", "))
+            }
+            Expression::Unary(operator, expression) => write!(f, "{}{}", operator, expression),
+            Expression::Binary(left, operator, right) => {
+                write!(f, "{} {} {}", left, operator, right)
+            }
+        }
+    }
+}
+
+fn precedence(operator: &str) -> u8 {
+    match operator {
+        "||" => 1,
+        "&&" => 2,
+        "==" | "!=" | "<" | "<=" | ">" | ">=" => 3,
+        "+" | "-" | "|" | "^" => 4,
+        "*" | "/" | "%" | "<<" | ">>" | "&" => 5,
+        _ => 0,
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token], position: usize) -> Parser<'a> {
+        Parser { tokens, position }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if s == symbol)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == symbol => Ok(()),
+            token => Err(format!("Expected `{}`, found {:?}", symbol, token)),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            token => Err(format!("Expected identifier, found {:?}", token)),
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        self.parse_binary(1)
+    }
+
+    fn parse_binary(&mut self, minimum: u8) -> Result<Expression, String> {
+        let mut left = self.parse_unary()?;
+
+        while let Some(Token::Symbol(operator)) = self.peek().cloned() {
+            let current = precedence(&operator);
+            if current == 0 || current < minimum {
+                break;
+            }
+            self.position += 1;
+            let right = self.parse_binary(current + 1)?;
+            left = Expression::Binary(Box::new(left), operator, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        match self.peek() {
+            Some(Token::Symbol(s))
+                if ["&", "*", "-", "+", "!", "^", "<-"].contains(&s.as_str()) =>
+            {
+                let operator = s.clone();
+                self.position += 1;
+                Ok(Expression::Unary(operator, Box::new(self.parse_unary()?)))
+            }
+            _ => {
+                let primary = self.parse_primary()?;
+                self.parse_postfix(primary)
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        match self.peek().cloned() {
+            Some(Token::String(value)) => {
+                self.position += 1;
+                Ok(Expression::String(value))
+            }
+            Some(Token::Number(value)) => {
+                self.position += 1;
+                Ok(Expression::Number(value))
+            }
+            Some(Token::Ident(name)) if name == "func" => self.skip_function(),
+            Some(Token::Ident(name)) if name == "map" => Ok(Expression::Type(self.parse_type()?)),
+            Some(Token::Ident(name)) => {
+                self.position += 1;
+                Ok(Expression::Ident(name))
+            }
+            Some(Token::Symbol(s)) if s == "(" => {
+                self.position += 1;
+                let expression = self.parse_expression()?;
+                self.expect_symbol(")")?;
+                Ok(expression)
+            }
+            Some(Token::Symbol(s)) if s == "[" => Ok(Expression::Type(self.parse_type()?)),
+            // Literal type can be elided inside another composite literal
+            Some(Token::Symbol(s)) if s == "{" => self.parse_composite(None),
+            token => Err(format!("Unexpected token {:?}", token)),
+        }
+    }
+
+    fn parse_postfix(&mut self, mut expression: Expression) -> Result<Expression, String> {
+        loop {
+            if self.is_symbol(".") {
+                self.position += 1;
+                if self.is_symbol("(") {
+                    // Type assertion, the asserted value is the same
+                    self.position += 1;
+                    self.parse_type()?;
+                    self.expect_symbol(")")?;
+                } else {
+                    expression = Expression::Selector(Box::new(expression), self.expect_ident()?);
+                }
+            } else if self.is_symbol("(") {
+                expression = Expression::Call(Box::new(expression), self.parse_arguments()?);
+            } else if self.is_symbol("[") {
+                self.position += 1;
+                let index = self.parse_expression()?;
+                self.expect_symbol("]")?;
+                expression = Expression::Index(Box::new(expression), Box::new(index));
+            } else if self.is_symbol("{")
+                && matches!(
+                    expression,
+                    Expression::Ident(_) | Expression::Selector(..) | Expression::Type(_)
+                )
+            {
+                expression = self.parse_composite(Some(expression))?;
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<Expression>, String> {
+        self.expect_symbol("(")?;
+        let mut arguments = vec![];
+
+        while !self.is_symbol(")") {
+            arguments.push(self.parse_expression()?);
+            if self.is_symbol("...") {
+                self.position += 1;
+            }
+            if !self.is_symbol(")") {
+                self.expect_symbol(",")?;
+            }
+        }
+        self.position += 1;
+
+        Ok(arguments)
+    }
+
+    fn parse_composite(&mut self, literal_type: Option<Expression>) -> Result<Expression, String> {
+        self.expect_symbol("{")?;
+        let mut elements = vec![];
+
+        while !self.is_symbol("}") {
+            let element = self.parse_expression()?;
+            if self.is_symbol(":") {
+                self.position += 1;
+                elements.push((Some(element), self.parse_expression()?));
+            } else {
+                elements.push((None, element));
+            }
+            if !self.is_symbol("}") {
+                self.expect_symbol(",")?;
+            }
+        }
+        self.position += 1;
+
+        Ok(Expression::Composite(literal_type.map(Box::new), elements))
+    }
+
+    fn parse_type(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == "*" => Ok(format!("*{}", self.parse_type()?)),
+            Some(Token::Symbol(s)) if s == "[" => {
+                let mut length = String::new();
+                while let Some(token) = self.next() {
+                    match token {
+                        Token::Symbol(s) if s == "]" => break,
+                        Token::Number(n) | Token::Ident(n) | Token::Symbol(n) => {
+                            length.push_str(&n)
+                        }
+                        Token::String(_) => return Err("Invalid array length".to_string()),
+                    }
+                }
+                Ok(format!("[{}]{}", length, self.parse_type()?))
+            }
+            Some(Token::Ident(name)) if name == "map" => {
+                self.expect_symbol("[")?;
+                let key = self.parse_type()?;
+                self.expect_symbol("]")?;
+                Ok(format!("map[{}]{}", key, self.parse_type()?))
+            }
+            Some(Token::Ident(name)) if name == "interface" => {
+                self.expect_symbol("{")?;
+                self.expect_symbol("}")?;
+                Ok("interface{}".to_string())
+            }
+            Some(Token::Ident(name)) => {
+                if self.is_symbol(".") {
+                    self.position += 1;
+                    Ok(format!("{}.{}", name, self.expect_ident()?))
+                } else {
+                    Ok(name)
+                }
+            }
+            token => Err(format!("Expected type, found {:?}", token)),
+        }
+    }
+
+    fn skip_function(&mut self) -> Result<Expression, String> {
+        // Skip the signature up to the body, then the balanced body
+        while !self.is_symbol("{") {
+            if self.next().is_none() {
+                return Err("Unterminated function literal".to_string());
+            }
+        }
+
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol(s) if s == "{" => depth += 1,
+                Token::Symbol(s) if s == "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(Expression::Function);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Err("Unterminated function literal".to_string())
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Null => "".to_string(),
+        v => v.to_string(),
+    }
+}
+
+// Go exported fields are PascalCase when blueprints are camelCase
+fn to_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn sprintf(arguments: &[Value]) -> Option<Value> {
+    let format = arguments.first()?.as_str()?;
+    let mut values = arguments.iter().skip(1);
+    let mut output = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            output.push('%');
+            continue;
+        }
+        // Flags and width are not relevant for a local emulation
+        for verb in chars.by_ref() {
+            if verb.is_alphabetic() {
+                break;
+            }
+        }
+        output.push_str(&values.next().map(value_to_string).unwrap_or_default());
+    }
+
+    Some(Value::String(output))
+}
+
+/***
+ * Pulumi SDK helper functions wrapping plain values, None when it cannot be resolved locally
+ */
+fn call_function(name: &str, arguments: &[Value]) -> Option<Value> {
+    match name {
+        "String" | "StringPtr" | "Int" | "IntPtr" | "Bool" | "BoolPtr" | "Float64"
+        | "Float64Ptr" | "Any" => arguments.first().cloned(),
+        "Sprintf" => sprintf(arguments),
+        _ => None,
+    }
+}
+
+fn to_value(expression: &Expression) -> Value {
+    match expression {
+        Expression::String(value) => Value::String(value.to_string()),
+        Expression::Number(value) => match value.parse::<i64>() {
+            Ok(number) => Value::from(number),
+            Err(_) => value
+                .parse::<f64>()
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::String(value.to_string())),
+        },
+        Expression::Ident(name) if name == "true" => Value::Bool(true),
+        Expression::Ident(name) if name == "false" => Value::Bool(false),
+        Expression::Ident(name) if name == "nil" => Value::Null,
+        // Output of another resource, kept as a reference like other languages
+        Expression::Selector(object, field) if matches!(**object, Expression::Ident(_)) => {
+            Value::String(format!("${{{}.{}}}", object, to_camel_case(field)))
+        }
+        Expression::Call(callee, arguments) => match &**callee {
+            Expression::Selector(package, name)
+                if **package == Expression::Ident("pulumi".to_string()) =>
+            {
+                let arguments: Vec<Value> = arguments.iter().map(to_value).collect();
+                call_function(name, &arguments)
+                    .unwrap_or_else(|| Value::String(expression.to_string()))
+            }
+            _ => Value::String(expression.to_string()),
+        },
+        Expression::Composite(literal_type, elements) => {
+            if !elements.is_empty() && elements.iter().all(|(key, _)| key.is_some()) {
+                let mut map = Map::new();
+                for (key, value) in elements {
+                    let key = match key {
+                        Some(Expression::Ident(name)) => to_camel_case(name),
+                        Some(key) => value_to_string(&to_value(key)),
+                        None => continue,
+                    };
+                    map.insert(key, to_value(value));
+                }
+                Value::Object(map)
+            } else {
+                let is_object = elements.is_empty()
+                    && !literal_type
+                        .as_ref()
+                        .map(|t| t.to_string())
+                        .is_some_and(|t| t.starts_with('[') || t.ends_with("Array"));
+                if is_object {
+                    Value::Object(Map::new())
+                } else {
+                    Value::Array(elements.iter().map(|(_, value)| to_value(value)).collect())
+                }
+            }
+        }
+        Expression::Unary(operator, expression) if operator == "&" => to_value(expression),
+        expression => Value::String(expression.to_string()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ResourceCall {
+    variable: Option<String>,
+    arguments: Vec<Expression>,
+}
+
+// Left most variable of `name, err := ...` or `name, err = ...`
+fn get_assigned_variable(tokens: &[Token], position: usize) -> Option<String> {
+    let mut index = position.checked_sub(1)?;
+    if !matches!(&tokens[index], Token::Symbol(s) if s == ":=" || s == "=") {
+        return None;
+    }
+
+    let mut variable = None;
+    while index > 0 {
+        index -= 1;
+        match &tokens[index] {
+            Token::Ident(name) => variable = Some(name.to_string()),
+            Token::Symbol(s) if s == "," => {}
+            _ => break,
+        }
+    }
+
+    variable.filter(|name| name != "_")
+}
+
+fn find_resources(
+    tokens: &[Token],
+    package: &str,
+    function: &str,
+) -> Result<Vec<ResourceCall>, String> {
+    let mut resources = vec![];
+
+    for (position, window) in tokens.windows(4).enumerate() {
+        let is_call = window[0] == Token::Ident(package.to_string())
+            && window[1] == Token::Symbol(".".to_string())
+            && window[2] == Token::Ident(function.to_string())
+            && window[3] == Token::Symbol("(".to_string());
+        if !is_call {
+            continue;
+        }
+
+        let mut parser = Parser::new(tokens, position + 3);
+        resources.push(ResourceCall {
+            variable: get_assigned_variable(tokens, position),
+            arguments: parser.parse_arguments()?,
+        });
+    }
+
+    Ok(resources)
+}
+
+fn get_images(tokens: &[Token]) -> Result<Vec<ContainerImageBluePrint>, String> {
+    let images = [
+        find_resources(tokens, "docker", "NewImage")?,
+        find_resources(tokens, "dockerbuild", "NewImage")?,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|resource| {
+        // Images cannot be referenced without a variable
+        let variable = resource.variable?;
+        let properties = to_value(resource.arguments.get(2)?);
+
+        pulumi::image::parse_image(Some(variable), &properties)
+    })
+    .collect();
+
+    Ok(images)
+}
+
+fn get_apps(tokens: &[Token]) -> Result<Vec<ContainerAppBluePrint>, String> {
+    [
+        find_resources(tokens, "app", "NewContainerApp")?,
+        find_resources(tokens, "app", "NewJob")?,
+    ]
+    .iter()
+    .flatten()
+    .map(|resource| {
+        let properties = resource
+            .arguments
+            .get(2)
+            .ok_or("Container app arguments need to be defined")?;
+
+        serde_json::from_value(to_value(properties)).map_err(|e| e.to_string())
+    })
+    .collect()
+}
+
+fn get_components(tokens: &[Token]) -> Result<Vec<DaprComponentBluePrint>, String> {
+    let components = find_resources(tokens, "app", "NewDaprComponent")?
+        .iter()
+        .filter_map(|resource| {
+            let name = to_value(resource.arguments.get(1)?);
+            let properties = to_value(resource.arguments.get(2)?);
+
+            pulumi::parse_dapr_component(name.as_str().map(|name| name.to_string()), properties)
+        })
+        .collect();
+
+    Ok(components)
+}
+
+pub fn deserialize(
+    input: &str,
+    modules: &[String],
+) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let mut images = vec![];
+    let mut apps = vec![];
+    let mut components = vec![];
+    // Each file is parsed on its own, resources of every file are built together
+    for module in [input]
+        .into_iter()
+        .chain(modules.iter().map(|module| module.as_str()))
+    {
+        let tokens = tokenize(module).map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+
+        images.append(&mut get_images(&tokens)?);
+        apps.append(&mut get_apps(&tokens)?);
+        components.append(&mut get_components(&tokens)?);
+    }
+
+    let services = pulumi::build_configuration(apps, images, components);
 
     match services {
         Some(val) => Ok(val),
         None => Err("No container to deserialize".to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::serializer::{
+        BuildContextBluePrint, ConfigurationBluePrint, ContainerBluePrint, IngressBluePrint,
+        TemplateBluePrint,
+    };
+
+    use super::*;
+
+    fn parse_expression(input: &str) -> Result<Expression, String> {
+        let tokens = tokenize(input)?;
+        Parser::new(&tokens, 0).parse_expression()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let output = tokenize("app, err := app.NewContainerApp(ctx, `app`, nil) // comment");
+        let expected = vec![
+            Token::Ident("app".to_string()),
+            Token::Symbol(",".to_string()),
+            Token::Ident("err".to_string()),
+            Token::Symbol(":=".to_string()),
+            Token::Ident("app".to_string()),
+            Token::Symbol(".".to_string()),
+            Token::Ident("NewContainerApp".to_string()),
+            Token::Symbol("(".to_string()),
+            Token::Ident("ctx".to_string()),
+            Token::Symbol(",".to_string()),
+            Token::String("app".to_string()),
+            Token::Symbol(",".to_string()),
+            Token::Ident("nil".to_string()),
+            Token::Symbol(")".to_string()),
+        ];
+        assert_eq!(Ok(expected), output);
+
+        let output = tokenize(r#""unterminated"#);
+        assert_eq!(Err("Unterminated string literal".to_string()), output);
+    }
+
+    #[test]
+    fn test_to_value() {
+        let expression = parse_expression(
+            r#"&app.IngressArgs{
+                External:   pulumi.Bool(true),
+                TargetPort: pulumi.IntPtr(80),
+                Transport:  pulumi.String("auto"),
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json!({ "external": true, "targetPort": 80, "transport": "auto" }),
+            to_value(&expression)
+        );
+
+        let expression = parse_expression(
+            r#"app.ContainerArray{
+                app.ContainerArgs{ Name: pulumi.String("myapp"), Image: newImage.ImageName },
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json!([{ "name": "myapp", "image": "${newImage.imageName}" }]),
+            to_value(&expression)
+        );
+
+        let expression =
+            parse_expression(r#"pulumi.Sprintf("%s/node-app:%d%%", registry.LoginServer, 1)"#)
+                .unwrap();
+        assert_eq!(
+            json!("${registry.loginServer}/node-app:1%"),
+            to_value(&expression)
+        );
+
+        let expression = parse_expression(r#"[]string{"a", "b"}"#).unwrap();
+        assert_eq!(json!(["a", "b"]), to_value(&expression));
+
+        let expression = parse_expression("managedEnvironment.ID()").unwrap();
+        assert_eq!(json!("managedEnvironment.ID()"), to_value(&expression));
+    }
+
+    #[test]
+    fn test_get_images() {
+        let data = r#"
+        newImage, err := docker.NewImage(ctx, "node-app", &docker.ImageArgs{
+            ImageName: pulumi.Sprintf("%s/node-app:v1.0.0", registry.LoginServer),
+            Build: docker.DockerBuildArgs{
+                Context: pulumi.String("./node-app"),
+            },
+        })
+        _, err = docker.NewImage(ctx, "unused", &docker.ImageArgs{
+            ImageName: pulumi.String("unused"),
+            Build:     docker.DockerBuildArgs{Context: pulumi.String("./unused")},
+        })
+        remote, err := docker.NewImage(ctx, "remote", &docker.ImageArgs{
+            ImageName: pulumi.String("nginx"),
+        })"#;
+
+        let output = get_images(&tokenize(data).unwrap());
+
+        let expected = vec![ContainerImageBluePrint {
+            name: Some("${registry.loginServer}/node-app:v1.0.0".to_string()),
+            build: BuildContextBluePrint {
+                context: "./node-app".to_string(),
+                ..Default::default()
+            },
+            reference_name: Some("newImage".to_string()),
+        }];
+
+        assert_eq!(Ok(expected), output);
+    }
+
+    #[test]
+    fn test_get_apps() {
+        let data = r#"
+        containerApp, err := app.NewContainerApp(ctx, "app", &app.ContainerAppArgs{
+            ResourceGroupName:    resourceGroup.Name,
+            ManagedEnvironmentId: managedEnvironment.ID(),
+            Configuration: app.ConfigurationArgs{
+                Ingress: app.IngressArgs{
+                    External:   pulumi.Bool(true),
+                    TargetPort: pulumi.IntPtr(80),
+                },
+            },
+            Template: app.TemplateArgs{
+                Containers: app.ContainerArray{
+                    app.ContainerArgs{
+                        Name:  pulumi.String("myapp"),
+                        Image: newImage.ImageName,
+                    },
+                },
+            },
+        })"#;
+
+        let output = get_apps(&tokenize(data).unwrap());
+
+        let expected = vec![ContainerAppBluePrint {
+            configuration: Some(ConfigurationBluePrint {
+                ingress: Some(IngressBluePrint {
+                    external: Some(true),
+                    target_port: Some(80),
+                }),
+                dapr: None,
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
+            }),
+            template: Some(TemplateBluePrint {
+                containers: Some(vec![ContainerBluePrint {
+                    name: "myapp".to_string(),
+                    image: "${newImage.imageName}".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
+                }]),
+                scale: None,
+                volumes: None,
+            }),
+        }];
+
+        assert_eq!(Ok(expected), output);
+
+        let output = get_apps(&tokenize(r#"app.NewContainerApp(ctx, "app")"#).unwrap());
+        assert_eq!(
+            Err("Container app arguments need to be defined".to_string()),
+            output
+        );
+    }
+}
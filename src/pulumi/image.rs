@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::serializer::{BuildContextBluePrint, ContainerImageBluePrint};
+
+fn get_string(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn get_strings(value: Option<&Value>) -> Option<Vec<String>> {
+    let values: Vec<String> = value?
+        .as_array()?
+        .iter()
+        .filter_map(|value| get_string(Some(value)))
+        .collect();
+
+    (!values.is_empty()).then_some(values)
+}
+
+fn get_map(value: Option<&Value>) -> Option<BTreeMap<String, String>> {
+    let map: BTreeMap<String, String> = value?
+        .as_object()?
+        .iter()
+        .filter_map(|(key, value)| Some((key.to_string(), get_string(Some(value))?)))
+        .collect();
+
+    (!map.is_empty()).then_some(map)
+}
+
+// Cache sources of `docker-build.Image` are objects, eg: `{ registry: { ref: "cache:latest" } }`
+fn get_cache_source(cache: &Value) -> Option<String> {
+    if let Some(raw) = cache.get("raw") {
+        return get_string(Some(raw));
+    }
+    if let Some(registry) = cache.get("registry") {
+        return Some(format!(
+            "type=registry,ref={}",
+            get_string(registry.get("ref"))?
+        ));
+    }
+    if let Some(local) = cache.get("local") {
+        return Some(format!("type=local,src={}", get_string(local.get("src"))?));
+    }
+    cache.get("gha").map(|_| "type=gha".to_string())
+}
+
+// SSH sockets or keys forwarded to the build, eg: `default` or `github=~/.ssh/id_rsa`
+fn get_ssh(ssh: &Value) -> Option<String> {
+    let id = get_string(ssh.get("id"))?;
+
+    match get_strings(ssh.get("paths")) {
+        Some(paths) => Some(format!("{}={}", id, paths.join(","))),
+        None => Some(id),
+    }
+}
+
+/***
+ * `docker.Image` build input, either a context path or its settings
+ */
+fn parse_docker_build(build: &Value) -> Option<BuildContextBluePrint> {
+    if let Some(context) = build.as_str() {
+        return Some(BuildContextBluePrint {
+            context: context.to_string(),
+            ..Default::default()
+        });
+    }
+
+    Some(BuildContextBluePrint {
+        context: get_string(build.get("context"))?,
+        dockerfile: get_string(build.get("dockerfile")),
+        args: get_map(build.get("args")),
+        target: get_string(build.get("target")),
+        platforms: get_string(build.get("platform")).map(|platform| vec![platform]),
+        cache_from: get_strings(build.get("cacheFrom").and_then(|c| c.get("images"))),
+        labels: get_map(build.get("labels")),
+        ssh: None,
+    })
+}
+
+/***
+ * `docker-build.Image` settings are properties of the resource, paths are nested in `location`
+ */
+fn parse_buildx_build(properties: &Value) -> Option<BuildContextBluePrint> {
+    let list = |key: &str| properties.get(key).and_then(|values| values.as_array());
+
+    Some(BuildContextBluePrint {
+        context: get_string(properties.get("context")?.get("location"))?,
+        dockerfile: get_string(properties.get("dockerfile").and_then(|d| d.get("location"))),
+        args: get_map(properties.get("buildArgs")),
+        target: get_string(properties.get("target")),
+        platforms: get_strings(properties.get("platforms")),
+        cache_from: list("cacheFrom")
+            .map(|caches| caches.iter().filter_map(get_cache_source).collect())
+            .filter(|caches: &Vec<String>| !caches.is_empty()),
+        labels: get_map(properties.get("labels")),
+        ssh: list("ssh")
+            .map(|ssh| ssh.iter().filter_map(get_ssh).collect())
+            .filter(|ssh: &Vec<String>| !ssh.is_empty()),
+    })
+}
+
+/***
+ * Image resource properties with camelCase keys, already pushed images have no build context
+ */
+pub fn parse_image(
+    reference_name: Option<String>,
+    properties: &Value,
+) -> Option<ContainerImageBluePrint> {
+    let build = match properties.get("build") {
+        Some(build) => parse_docker_build(build),
+        None => parse_buildx_build(properties),
+    }?;
+
+    let name = get_string(properties.get("imageName"))
+        .or_else(|| get_string(properties.get("name")))
+        .or_else(|| get_strings(properties.get("tags"))?.first().cloned());
+
+    Some(ContainerImageBluePrint {
+        name,
+        build,
+        reference_name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_image() {
+        // `docker.Image` with a plain context
+        let output = parse_image(
+            Some("remix".to_string()),
+            &json!({ "imageName": "node-18", "build": "../frontend" }),
+        );
+
+        let expected = ContainerImageBluePrint {
+            name: Some("node-18".to_string()),
+            build: BuildContextBluePrint {
+                context: "../frontend".to_string(),
+                ..Default::default()
+            },
+            reference_name: Some("remix".to_string()),
+        };
+        assert_eq!(Some(expected), output);
+
+        // `docker.Image` with build settings
+        let output = parse_image(
+            Some("api".to_string()),
+            &json!({
+                "imageName": "registry.io/api:v1",
+                "build": {
+                    "context": "./api",
+                    "dockerfile": "./api/Dockerfile.prod",
+                    "args": { "NODE_VERSION": 18 },
+                    "target": "runtime",
+                    "platform": "linux/amd64",
+                    "cacheFrom": { "images": ["registry.io/api:cache"] }
+                }
+            }),
+        );
+
+        let expected = BuildContextBluePrint {
+            context: "./api".to_string(),
+            dockerfile: Some("./api/Dockerfile.prod".to_string()),
+            args: Some(BTreeMap::from([(
+                "NODE_VERSION".to_string(),
+                "18".to_string(),
+            )])),
+            target: Some("runtime".to_string()),
+            platforms: Some(vec!["linux/amd64".to_string()]),
+            cache_from: Some(vec!["registry.io/api:cache".to_string()]),
+            labels: None,
+            ssh: None,
+        };
+        assert_eq!(Some(expected), output.map(|image| image.build));
+
+        // `docker-build.Image` settings
+        let output = parse_image(
+            Some("worker".to_string()),
+            &json!({
+                "tags": ["registry.io/worker:v1"],
+                "context": { "location": "./worker" },
+                "dockerfile": { "location": "./worker/Dockerfile" },
+                "buildArgs": { "MODE": "release" },
+                "platforms": ["linux/amd64", "linux/arm64"],
+                "cacheFrom": [
+                    { "registry": { "ref": "registry.io/worker:cache" } },
+                    { "local": { "src": "/tmp/cache" } },
+                    { "raw": "type=gha" }
+                ],
+                "labels": { "team": "core" },
+                "ssh": [{ "id": "default" }, { "id": "github", "paths": ["~/.ssh/id_rsa"] }],
+                "push": true
+            }),
+        )
+        .unwrap();
+
+        let expected = ContainerImageBluePrint {
+            name: Some("registry.io/worker:v1".to_string()),
+            build: BuildContextBluePrint {
+                context: "./worker".to_string(),
+                dockerfile: Some("./worker/Dockerfile".to_string()),
+                args: Some(BTreeMap::from([(
+                    "MODE".to_string(),
+                    "release".to_string(),
+                )])),
+                target: None,
+                platforms: Some(vec!["linux/amd64".to_string(), "linux/arm64".to_string()]),
+                cache_from: Some(vec![
+                    "type=registry,ref=registry.io/worker:cache".to_string(),
+                    "type=local,src=/tmp/cache".to_string(),
+                    "type=gha".to_string(),
+                ]),
+                labels: Some(BTreeMap::from([("team".to_string(), "core".to_string())])),
+                ssh: Some(vec![
+                    "default".to_string(),
+                    "github=~/.ssh/id_rsa".to_string(),
+                ]),
+            },
+            reference_name: Some("worker".to_string()),
+        };
+        assert_eq!(expected, output);
+
+        // Without build context, the image is already pushed
+        assert_eq!(None, parse_image(None, &json!({ "imageName": "nginx" })));
+    }
+}
@@ -1,150 +1,1315 @@
-use crate::pulumi;
-use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{error, warn};
+use serde_json::{Map, Value};
 
+use crate::pulumi;
+use crate::pulumi::config::{self, Config};
 use crate::serializer::{
-    BuildContextBluePrint, ContainerAppBluePrint, ContainerAppConfiguration,
-    ContainerImageBluePrint,
+    ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
 };
 
-fn parse_line(line: &str) -> String {
-    let a = line.replace(" ", "");
-    let re = Regex::new(r####"([a-zA-Z"]+)(:)([a-zA-Z0-9-:.`'/"\{}\[\]]+)?"####).unwrap();
-
-    let captures = re.captures(&a);
-    let computed = match captures {
-        Some(c) => {
-            let key = c.get(1).unwrap().as_str();
-            let value = if c.get(3).is_some() {
-                let computed = c.get(3).unwrap().as_str();
-                let tokens = ["{", "[{"];
-                let has_token = tokens.contains(&computed);
-
-                if has_token {
-                    computed.to_string()
+#[derive(Debug, Clone, PartialEq)]
+enum Fragment {
+    Text(String),
+    // Source of a `${...}` substitution
+    Expression(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Template(Vec<Fragment>),
+    Number(String),
+    Symbol(String),
+}
+
+const SYMBOLS: [&str; 20] = [
+    "===", "!==", "...", "**", "=>", "==", "!=", "<=", ">=", "&&", "||", "??", "?.", "++", "--",
+    "+=", "-=", "*=", "/=", "|=",
+];
+
+fn read_escape(chars: &[char], position: &mut usize) -> char {
+    let escaped = chars.get(*position).copied().unwrap_or_default();
+    *position += 1;
+    match escaped {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        c => c,
+    }
+}
+
+fn read_string(chars: &[char], position: &mut usize) -> Result<String, String> {
+    let quote = chars[*position];
+    let mut output = String::new();
+    *position += 1;
+
+    while *position < chars.len() {
+        let c = chars[*position];
+        *position += 1;
+
+        match c {
+            c if c == quote => return Ok(output),
+            '\\' => output.push(read_escape(chars, position)),
+            '\n' => break,
+            c => output.push(c),
+        }
+    }
+
+    Err("Unterminated string literal".to_string())
+}
+
+fn read_template(chars: &[char], position: &mut usize) -> Result<Vec<Fragment>, String> {
+    let mut fragments = vec![];
+    let mut text = String::new();
+    *position += 1;
+
+    while *position < chars.len() {
+        let c = chars[*position];
+        *position += 1;
+
+        match c {
+            '`' => {
+                if !text.is_empty() {
+                    fragments.push(Fragment::Text(text));
+                }
+                return Ok(fragments);
+            }
+            '\\' => text.push(read_escape(chars, position)),
+            '$' if chars.get(*position) == Some(&'{') => {
+                if !text.is_empty() {
+                    fragments.push(Fragment::Text(std::mem::take(&mut text)));
+                }
+                *position += 1;
+
+                // Substitutions can contain objects, strings or other templates
+                let start = *position;
+                let mut depth = 0;
+                while *position < chars.len() && !(chars[*position] == '}' && depth == 0) {
+                    match chars[*position] {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        '"' | '\'' => {
+                            read_string(chars, position)?;
+                            continue;
+                        }
+                        '`' => {
+                            read_template(chars, position)?;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                    *position += 1;
+                }
+                let expression: String = chars[start..*position].iter().collect();
+                fragments.push(Fragment::Expression(expression.trim().to_string()));
+                *position += 1;
+            }
+            c => text.push(c),
+        }
+    }
+
+    Err("Unterminated template literal".to_string())
+}
+
+// A slash starts a regular expression where a value is expected
+fn is_regex_allowed(previous: Option<&Token>) -> bool {
+    match previous {
+        None => true,
+        Some(Token::Symbol(s)) => ![")", "]", "}"].contains(&s.as_str()),
+        Some(Token::Ident(name)) => {
+            ["return", "typeof", "case", "in", "of"].contains(&name.as_str())
+        }
+        _ => false,
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while position < chars.len() {
+        let c = chars[position];
+        let next = chars.get(position + 1).copied().unwrap_or_default();
+
+        if c.is_whitespace() {
+            position += 1;
+        } else if c == '/' && next == '/' {
+            while position < chars.len() && chars[position] != '\n' {
+                position += 1;
+            }
+        } else if c == '/' && next == '*' {
+            position += 2;
+            while position < chars.len() && !(chars[position - 1] == '*' && chars[position] == '/')
+            {
+                position += 1;
+            }
+            position += 1;
+        } else if c == '/' && is_regex_allowed(tokens.last()) {
+            let start = position;
+            position += 1;
+            let mut is_class = false;
+            while position < chars.len() && (chars[position] != '/' || is_class) {
+                match chars[position] {
+                    '\\' => position += 1,
+                    '[' => is_class = true,
+                    ']' => is_class = false,
+                    '\n' => return Err("Unterminated regular expression".to_string()),
+                    _ => {}
+                }
+                position += 1;
+            }
+            position += 1;
+            while position < chars.len() && chars[position].is_alphabetic() {
+                position += 1;
+            }
+            // Kept as an opaque value
+            tokens.push(Token::Ident(
+                chars[start..position.min(chars.len())].iter().collect(),
+            ));
+        } else if c == '"' || c == '\'' {
+            tokens.push(Token::String(read_string(&chars, &mut position)?));
+        } else if c == '`' {
+            tokens.push(Token::Template(read_template(&chars, &mut position)?));
+        } else if c.is_ascii_digit() || (c == '.' && next.is_ascii_digit()) {
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric()
+                    || chars[position] == '.'
+                    || chars[position] == '_')
+            {
+                position += 1;
+            }
+            tokens.push(Token::Number(chars[start..position].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric()
+                    || chars[position] == '_'
+                    || chars[position] == '$')
+            {
+                position += 1;
+            }
+            tokens.push(Token::Ident(chars[start..position].iter().collect()));
+        } else {
+            let rest: String = chars[position..chars.len().min(position + 3)]
+                .iter()
+                .collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(*symbol))
+                .map(|symbol| symbol.to_string())
+                .unwrap_or_else(|| c.to_string());
+
+            position += symbol.chars().count();
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Property {
+    KeyValue(String, Expression),
+    // `[key]: value`, the key cannot be resolved
+    Computed(Expression, Expression),
+    Spread(Expression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    String(String),
+    Template(Vec<Fragment>),
+    Number(String),
+    Ident(String),
+    // Functions are kept as a placeholder, their body is never evaluated
+    Function,
+    Member(Box<Expression>, String),
+    Index(Box<Expression>, Box<Expression>),
+    Call(Box<Expression>, Vec<Expression>),
+    TaggedTemplate(Box<Expression>, Vec<Fragment>),
+    New(Box<Expression>, Vec<Expression>),
+    Object(Vec<Property>),
+    Array(Vec<Expression>),
+    Spread(Box<Expression>),
+    Unary(String, Box<Expression>),
+    Binary(Box<Expression>, String, Box<Expression>),
+    Conditional(Box<Expression>, Box<Expression>, Box<Expression>),
+}
+
+fn join<T: fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn template_to_string(fragments: &[Fragment]) -> String {
+    fragments
+        .iter()
+        .map(|fragment| match fragment {
+            Fragment::Text(text) => text.to_string(),
+            Fragment::Expression(expression) => format!("${{{}}}", expression),
+        })
+        .collect()
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Property::KeyValue(key, value) => write!(f, "{}: {}", key, value),
+            Property::Computed(key, value) => write!(f, "[{}]: {}", key, value),
+            Property::Spread(value) => write!(f, "...{}", value),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::String(value) => write!(f, "{:?}", value),
+            Expression::Template(fragments) => write!(f, "`{}`", template_to_string(fragments)),
+            Expression::Number(value) | Expression::Ident(value) => write!(f, "{}", value),
+            Expression::Function => write!(f, "() => {{...}}"),
+            Expression::Member(object, property) => write!(f, "{}.{}", object, property),
+            Expression::Index(object, index) => write!(f, "{}[{}]", object, index),
+            Expression::Call(callee, arguments) => write!(f, "{}({})", callee, join(arguments)),
+            Expression::TaggedTemplate(tag, fragments) => {
+                write!(f, "{}`{}`", tag, template_to_string(fragments))
+            }
+            Expression::New(callee, arguments) => {
+                write!(f, "new {}({})", callee, join(arguments))
+            }
+            Expression::Object(properties) => write!(f, "{{ {} }}", join(properties)),
+            Expression::Array(values) => write!(f, "[{}]", join(values)),
+            Expression::Spread(value) => write!(f, "...{}", value),
+            Expression::Unary(operator, value) => match operator.chars().next() {
+                Some(c) if c.is_alphabetic() => write!(f, "{} {}", operator, value),
+                _ => write!(f, "{}{}", operator, value),
+            },
+            Expression::Binary(left, operator, right) => {
+                write!(f, "{} {} {}", left, operator, right)
+            }
+            Expression::Conditional(condition, value, alternative) => {
+                write!(f, "{} ? {} : {}", condition, value, alternative)
+            }
+        }
+    }
+}
+
+fn precedence(token: &Token) -> Option<(String, u8)> {
+    let operator = match token {
+        Token::Symbol(symbol) => symbol.as_str(),
+        Token::Ident(name) if name == "instanceof" || name == "in" => name.as_str(),
+        _ => return None,
+    };
+
+    let precedence = match operator {
+        "??" => 1,
+        "||" => 2,
+        "&&" => 3,
+        "|" => 4,
+        "&" => 5,
+        "==" | "!=" | "===" | "!==" => 6,
+        "<" | ">" | "<=" | ">=" | "instanceof" | "in" => 7,
+        "+" | "-" => 8,
+        "*" | "/" | "%" => 9,
+        "**" => 10,
+        _ => return None,
+    };
+
+    Some((operator.to_string(), precedence))
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token], position: usize) -> Parser<'a> {
+        Parser { tokens, position }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        self.is_symbol_at(self.position, symbol)
+    }
+
+    fn is_symbol_at(&self, position: usize, symbol: &str) -> bool {
+        matches!(self.tokens.get(position), Some(Token::Symbol(s)) if s == symbol)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(name)) if name == keyword)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == symbol => Ok(()),
+            token => Err(format!("Expected `{}`, found {:?}", symbol, token)),
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            token => Err(format!("Expected identifier, found {:?}", token)),
+        }
+    }
+
+    // Position of the bracket closing the one at the given position
+    fn find_closing(&self, position: usize) -> Option<usize> {
+        let mut depth = 0;
+
+        for (index, token) in self.tokens.iter().enumerate().skip(position) {
+            match token {
+                Token::Symbol(s) if ["(", "[", "{"].contains(&s.as_str()) => depth += 1,
+                Token::Symbol(s) if [")", "]", "}"].contains(&s.as_str()) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn skip_brackets(&mut self) -> Result<(), String> {
+        match self.find_closing(self.position) {
+            Some(closing) => {
+                self.position = closing + 1;
+                Ok(())
+            }
+            None => Err("Unbalanced brackets".to_string()),
+        }
+    }
+
+    // Type annotations are not needed to read values
+    fn skip_type(&mut self) -> Result<(), String> {
+        loop {
+            match self.peek() {
+                Some(Token::Symbol(s)) if s == "(" || s == "{" || s == "[" => {
+                    self.skip_brackets()?
+                }
+                Some(Token::Ident(_) | Token::String(_) | Token::Number(_)) => {
+                    self.position += 1;
+                    while self.is_symbol(".") {
+                        self.position += 2;
+                    }
+                }
+                token => return Err(format!("Expected type, found {:?}", token)),
+            }
+
+            if self.is_symbol("<") {
+                let mut depth = 0;
+                while let Some(token) = self.next() {
+                    match token {
+                        Token::Symbol(s) if s == "<" => depth += 1,
+                        Token::Symbol(s) if s == ">" => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            while self.is_symbol("[") && self.is_symbol_at(self.position + 1, "]") {
+                self.position += 2;
+            }
+            if self.is_symbol("=>") {
+                self.position += 1;
+                continue;
+            }
+            if !(self.is_symbol("|") || self.is_symbol("&")) {
+                return Ok(());
+            }
+            self.position += 1;
+        }
+    }
+
+    fn is_arrow_function(&self) -> bool {
+        match self.peek() {
+            Some(Token::Ident(_)) => self.is_symbol_at(self.position + 1, "=>"),
+            Some(Token::Symbol(s)) if s == "(" => match self.find_closing(self.position) {
+                // Return type annotation between parameters and arrow
+                Some(closing) => {
+                    self.is_symbol_at(closing + 1, "=>") || self.is_symbol_at(closing + 1, ":")
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn skip_function(&mut self) -> Result<Expression, String> {
+        if self.is_keyword("function") {
+            self.position += 1;
+            if matches!(self.peek(), Some(Token::Ident(_))) {
+                self.position += 1;
+            }
+            self.skip_brackets()?;
+            if self.is_symbol(":") {
+                self.position += 1;
+                self.skip_type()?;
+            }
+            self.skip_brackets()?;
+            return Ok(Expression::Function);
+        }
+
+        if self.is_symbol("(") {
+            self.skip_brackets()?;
+            if self.is_symbol(":") {
+                self.position += 1;
+                self.skip_type()?;
+            }
+        } else {
+            self.position += 1;
+        }
+        self.expect_symbol("=>")?;
+
+        if self.is_symbol("{") {
+            self.skip_brackets()?;
+        } else {
+            self.parse_expression()?;
+        }
+
+        Ok(Expression::Function)
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        if self.is_keyword("async")
+            && !self.is_symbol_at(self.position + 1, "(")
+            && !self.is_symbol_at(self.position + 1, ",")
+        {
+            self.position += 1;
+        }
+        if self.is_keyword("async") && self.is_symbol_at(self.position + 1, "(") {
+            self.position += 1;
+        }
+        if self.is_arrow_function() || self.is_keyword("function") {
+            return self.skip_function();
+        }
+
+        let condition = self.parse_binary(1)?;
+        if !self.is_symbol("?") {
+            return Ok(condition);
+        }
+
+        self.position += 1;
+        let value = self.parse_expression()?;
+        self.expect_symbol(":")?;
+        let alternative = self.parse_expression()?;
+
+        Ok(Expression::Conditional(
+            Box::new(condition),
+            Box::new(value),
+            Box::new(alternative),
+        ))
+    }
+
+    fn parse_binary(&mut self, minimum: u8) -> Result<Expression, String> {
+        let mut left = self.parse_unary()?;
+
+        while let Some((operator, current)) = self.peek().and_then(precedence) {
+            if current < minimum {
+                break;
+            }
+            self.position += 1;
+            let right = self.parse_binary(current + 1)?;
+            left = Expression::Binary(Box::new(left), operator, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        let operator = match self.peek() {
+            Some(Token::Symbol(s)) if ["!", "-", "+", "~"].contains(&s.as_str()) => s.to_string(),
+            Some(Token::Ident(name)) if ["typeof", "await", "void"].contains(&name.as_str()) => {
+                name.to_string()
+            }
+            _ => {
+                let primary = self.parse_primary()?;
+                return self.parse_postfix(primary);
+            }
+        };
+
+        self.position += 1;
+        Ok(Expression::Unary(operator, Box::new(self.parse_unary()?)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::String(value)) => Ok(Expression::String(value)),
+            Some(Token::Template(fragments)) => Ok(Expression::Template(fragments)),
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::Ident(name)) if name == "new" => {
+                let callee = self.parse_callee()?;
+                let arguments = if self.is_symbol("(") {
+                    self.parse_arguments()?
                 } else {
-                    let with_quotes = format!("\"{}\",", computed);
-                    // Check if it's a number or a boolean
-                    let re = Regex::new(r"^[0-9]+").unwrap().is_match(computed);
-                    // Need to cleanup this part
-                    if re || computed == "true" || computed == "false" {
-                        format!("{},", computed.to_string())
-                    } else {
-                        with_quotes.to_string()
+                    vec![]
+                };
+                Ok(Expression::New(Box::new(callee), arguments))
+            }
+            Some(Token::Ident(name)) => Ok(Expression::Ident(name)),
+            Some(Token::Symbol(s)) if s == "(" => {
+                let expression = self.parse_expression()?;
+                self.expect_symbol(")")?;
+                Ok(expression)
+            }
+            Some(Token::Symbol(s)) if s == "[" => Ok(Expression::Array(self.parse_list("]")?)),
+            Some(Token::Symbol(s)) if s == "{" => self.parse_object(),
+            token => Err(format!("Unexpected token {:?}", token)),
+        }
+    }
+
+    // Constructor of a `new` expression, eg: `docker.Image` or `Map<string, string>`
+    fn parse_callee(&mut self) -> Result<Expression, String> {
+        let mut callee = Expression::Ident(self.expect_name()?);
+        while self.is_symbol(".") {
+            self.position += 1;
+            callee = Expression::Member(Box::new(callee), self.expect_name()?);
+        }
+        if self.is_symbol("<") {
+            self.skip_type_arguments();
+        }
+        Ok(callee)
+    }
+
+    fn skip_type_arguments(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol(s) if s == "<" => depth += 1,
+                Token::Symbol(s) if s == ">" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
                     }
                 }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_list(&mut self, closing: &str) -> Result<Vec<Expression>, String> {
+        let mut values = vec![];
+
+        while !self.is_symbol(closing) {
+            if self.is_symbol("...") {
+                self.position += 1;
+                values.push(Expression::Spread(Box::new(self.parse_expression()?)));
+            } else if self.is_symbol(",") {
+                // Hole in an array literal
+                values.push(Expression::Ident("undefined".to_string()));
             } else {
-                "".to_string()
-            };
+                values.push(self.parse_expression()?);
+            }
 
-            let key = format!("\"{}\"", key).replace("\"\"", "\"");
-            let value = value
-                .replace("\"\"", "\"")
-                .replace("`", "")
-                .replace("'", "");
+            if !self.is_symbol(closing) {
+                self.expect_symbol(",")?;
+            }
+        }
+        self.position += 1;
 
-            let mut computed = format!("{key}:{value}");
+        Ok(values)
+    }
 
-            // TODO: clean that
-            if value.contains("{}") {
-                computed = "".to_string();
+    fn parse_object(&mut self) -> Result<Expression, String> {
+        let mut properties = vec![];
+
+        while !self.is_symbol("}") {
+            if self.is_symbol("...") {
+                self.position += 1;
+                properties.push(Property::Spread(self.parse_expression()?));
+            } else if self.is_symbol("[") {
+                self.position += 1;
+                let key = self.parse_expression()?;
+                self.expect_symbol("]")?;
+                self.expect_symbol(":")?;
+                properties.push(Property::Computed(key, self.parse_expression()?));
+            } else {
+                let key = match self.next() {
+                    Some(Token::Ident(name) | Token::String(name) | Token::Number(name)) => name,
+                    token => return Err(format!("Expected property name, found {:?}", token)),
+                };
+
+                if self.is_symbol(":") {
+                    self.position += 1;
+                    properties.push(Property::KeyValue(key, self.parse_expression()?));
+                } else if self.is_symbol("(") {
+                    // Method shorthand
+                    self.skip_brackets()?;
+                    self.skip_brackets()?;
+                    properties.push(Property::KeyValue(key, Expression::Function));
+                } else {
+                    // Shorthand property
+                    properties.push(Property::KeyValue(key.to_string(), Expression::Ident(key)));
+                }
             }
 
-            computed
+            if !self.is_symbol("}") {
+                self.expect_symbol(",")?;
+            }
         }
-        None => a.to_string(),
-    };
+        self.position += 1;
 
-    computed
+        Ok(Expression::Object(properties))
+    }
+
+    fn parse_postfix(&mut self, mut expression: Expression) -> Result<Expression, String> {
+        loop {
+            match self.peek().cloned() {
+                Some(Token::Symbol(s)) if s == "." || s == "?." => {
+                    self.position += 1;
+                    if self.is_symbol("(") {
+                        // Optional call, eg: `fn?.()`
+                        continue;
+                    }
+                    expression = Expression::Member(Box::new(expression), self.expect_name()?);
+                }
+                Some(Token::Symbol(s)) if s == "(" => {
+                    expression = Expression::Call(Box::new(expression), self.parse_arguments()?);
+                }
+                Some(Token::Symbol(s)) if s == "[" => {
+                    self.position += 1;
+                    let index = self.parse_expression()?;
+                    self.expect_symbol("]")?;
+                    expression = Expression::Index(Box::new(expression), Box::new(index));
+                }
+                Some(Token::Symbol(s))
+                    if s == "!" && !self.is_symbol_at(self.position + 1, "=") =>
+                {
+                    // Non-null assertion
+                    self.position += 1;
+                }
+                Some(Token::Template(fragments)) => {
+                    self.position += 1;
+                    expression = Expression::TaggedTemplate(Box::new(expression), fragments);
+                }
+                Some(Token::Ident(name)) if name == "as" || name == "satisfies" => {
+                    self.position += 1;
+                    self.skip_type()?;
+                }
+                _ => return Ok(expression),
+            }
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<Expression>, String> {
+        self.expect_symbol("(")?;
+        self.parse_list(")")
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Null => "".to_string(),
+        v => v.to_string(),
+    }
+}
+
+/***
+ * Pulumi SDK functions, None when it cannot be resolved locally
+ */
+fn call_function(name: &str, arguments: &[Value]) -> Option<Value> {
+    match name {
+        "pulumi.concat" => Some(Value::String(
+            arguments.iter().map(value_to_string).collect(),
+        )),
+        "pulumi.output" | "pulumi.secret" | "pulumi.unsecret" | "String" => {
+            arguments.first().cloned()
+        }
+        "Number" => value_to_string(arguments.first()?)
+            .parse::<f64>()
+            .ok()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+/***
+ * Values of the `const`, `let` and `var` declared at the top level of the program
+ */
+#[derive(Debug, Default, PartialEq)]
+struct Symbols {
+    values: HashMap<String, Value>,
+    /// Namespaces of the `pulumi.Config` instances, None for the project
+    configs: HashMap<String, Option<String>>,
+    config: Config,
 }
 
-fn prune_output(output: String) -> String {
-    //  s = s.replace("})", "}").replace(",}", "}");
-    output.replace("})", "}").replace(",}", "}")
+fn parse_number(value: &str) -> Value {
+    let value = value.replace('_', "");
+    match value.parse::<i64>() {
+        Ok(number) => Value::from(number),
+        Err(_) => value
+            .parse::<f64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(value.to_string())),
+    }
+}
+
+// Substitutions are resolved when possible, kept as `${...}` otherwise
+fn resolve_template(fragments: &[Fragment], symbols: &Symbols) -> String {
+    fragments
+        .iter()
+        .map(|fragment| match fragment {
+            Fragment::Text(text) => text.to_string(),
+            Fragment::Expression(source) => tokenize(source)
+                .and_then(|tokens| Parser::new(&tokens, 0).parse_expression())
+                .ok()
+                .and_then(|expression| resolve(&expression, symbols))
+                .map(|value| value_to_string(&value))
+                .unwrap_or_else(|| format!("${{{}}}", source)),
+        })
+        .collect()
+}
+
+fn get_namespace(arguments: &[Expression]) -> Option<String> {
+    match arguments.first() {
+        Some(Expression::String(namespace)) => Some(namespace.to_string()),
+        _ => None,
+    }
 }
 
-fn get_images(input: &str) -> Vec<ContainerImageBluePrint> {
-    let images_services: Vec<(String, String, Option<String>)> =
-        Regex::new(r####"((const|let) ?(?P<serviceName>.+) ?= ?)?new docker.Image\("(?P<name>.+)",( ?)(?P<value>\{(\n.+)+[^;s"\n.+])"####)
-            .unwrap()
-            .captures_iter(&input)
-            .map(|container| {
+// Getter of a `pulumi.Config`, eg: `config.require("key")` or `new pulumi.Config("namespace").get("key")`
+fn is_config(callee: &Expression, symbols: &Symbols) -> bool {
+    match callee {
+        Expression::Member(object, _) => match &**object {
+            Expression::Ident(name) => symbols.configs.contains_key(name),
+            Expression::New(class, _) => class.to_string() == "pulumi.Config",
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/***
+ * Stack value read by a `pulumi.Config` getter, converted to the type of the getter, eg: `getNumber`
+ */
+fn get_config(callee: &Expression, arguments: &[Expression], symbols: &Symbols) -> Option<Value> {
+    let Expression::Member(object, getter) = callee else {
+        return None;
+    };
+    let namespace = match &**object {
+        Expression::Ident(name) => symbols.configs.get(name)?.clone(),
+        Expression::New(_, arguments) => get_namespace(arguments),
+        _ => return None,
+    };
+    let key = match arguments.first()? {
+        Expression::String(key) => key,
+        _ => return None,
+    };
 
-                let service_name = match container.name("serviceName") {
-                    Some(v) => {
-                        Some(v.as_str().trim().to_string())
+    let value = symbols.config.get(namespace.as_deref(), key)?;
+    let value_type = getter
+        .trim_start_matches("get")
+        .trim_start_matches("require")
+        .trim_start_matches("Secret");
+
+    Some(config::convert(value, value_type))
+}
+
+/***
+ * Evaluate an expression from literals and declared symbols, None when it depends on runtime values
+ */
+fn resolve(expression: &Expression, symbols: &Symbols) -> Option<Value> {
+    match expression {
+        Expression::String(value) => Some(Value::String(value.to_string())),
+        Expression::Template(fragments) => {
+            Some(Value::String(resolve_template(fragments, symbols)))
+        }
+        Expression::TaggedTemplate(tag, fragments) if tag.to_string() == "pulumi.interpolate" => {
+            Some(Value::String(resolve_template(fragments, symbols)))
+        }
+        Expression::Number(value) => Some(parse_number(value)),
+        Expression::Ident(name) if name == "true" => Some(Value::Bool(true)),
+        Expression::Ident(name) if name == "false" => Some(Value::Bool(false)),
+        Expression::Ident(name) if name == "null" || name == "undefined" => Some(Value::Null),
+        Expression::Ident(name) => symbols.values.get(name).cloned(),
+        Expression::Member(object, property) => match &**object {
+            Expression::Ident(name) if symbols.values.contains_key(name) => {
+                symbols.values.get(name)?.get(property).cloned()
+            }
+            // Output of a resource, kept as a reference like other languages
+            Expression::Ident(name) if name != "pulumi" && name != "process" => {
+                Some(Value::String(format!("${{{}.{}}}", name, property)))
+            }
+            object => resolve(object, symbols)?.get(property).cloned(),
+        },
+        Expression::Index(object, index) => {
+            let object = resolve(object, symbols)?;
+            match resolve(index, symbols)? {
+                Value::String(key) => object.get(key).cloned(),
+                Value::Number(index) => object.get(index.as_u64()? as usize).cloned(),
+                _ => None,
+            }
+        }
+        Expression::Call(callee, arguments) if is_config(callee, symbols) => {
+            get_config(callee, arguments, symbols)
+        }
+        Expression::Call(callee, arguments) => {
+            let values: Vec<Value> = arguments
+                .iter()
+                .map(|argument| to_value(argument, symbols))
+                .collect();
+            call_function(&callee.to_string(), &values)
+        }
+        Expression::Object(properties) => {
+            let mut map = Map::new();
+            for property in properties {
+                match property {
+                    Property::KeyValue(key, value) => {
+                        let value = to_value(value, symbols);
+                        // Empty objects are left out like undefined values
+                        if value.as_object().is_some_and(|o| o.is_empty()) {
+                            continue;
+                        }
+                        map.insert(key.to_string(), value);
+                    }
+                    Property::Spread(value) => {
+                        if let Some(Value::Object(spread)) = resolve(value, symbols) {
+                            map.extend(spread);
+                        }
+                    }
+                    Property::Computed(..) => {}
+                }
+            }
+            Some(Value::Object(map))
+        }
+        Expression::Array(values) => {
+            let mut output = vec![];
+            for value in values {
+                match value {
+                    Expression::Spread(spread) => match to_value(spread, symbols) {
+                        Value::Array(spread) => output.extend(spread),
+                        spread => output.push(spread),
                     },
-                    None => None,
+                    value => output.push(to_value(value, symbols)),
+                }
+            }
+            Some(Value::Array(output))
+        }
+        Expression::Unary(operator, value) if operator == "-" => match resolve(value, symbols)? {
+            Value::Number(number) => match number.as_i64() {
+                Some(number) => Some(Value::from(-number)),
+                None => Some(Value::from(-number.as_f64()?)),
+            },
+            _ => None,
+        },
+        Expression::Binary(left, operator, right) if operator == "+" => {
+            match (resolve(left, symbols)?, resolve(right, symbols)?) {
+                (Value::String(left), right) => {
+                    Some(Value::String(left + &value_to_string(&right)))
+                }
+                (left, Value::String(right)) => {
+                    Some(Value::String(value_to_string(&left) + &right))
+                }
+                (Value::Number(left), Value::Number(right)) => {
+                    match (left.as_i64(), right.as_i64()) {
+                        (Some(left), Some(right)) => Some(Value::from(left + right)),
+                        _ => Some(Value::from(left.as_f64()? + right.as_f64()?)),
+                    }
+                }
+                _ => None,
+            }
+        }
+        // Defaults are used when the value is only known at deployment, eg: `config.getNumber("port") ?? 3000`
+        Expression::Binary(left, operator, right) if operator == "??" || operator == "||" => {
+            match resolve(left, symbols) {
+                Some(value) if !value.is_null() && value != Value::Bool(false) => Some(value),
+                _ => resolve(right, symbols),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn to_value(expression: &Expression, symbols: &Symbols) -> Value {
+    resolve(expression, symbols).unwrap_or_else(|| Value::String(expression.to_string()))
+}
+
+/***
+ * Symbol table of the top level declarations, resources are left out to be referenced by their outputs
+ */
+fn get_symbols(tokens: &[Token], config: &Config) -> Symbols {
+    let mut symbols = Symbols {
+        config: config.clone(),
+        ..Default::default()
+    };
+    let mut depth = 0;
+
+    for (position, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Symbol(s) if ["(", "[", "{"].contains(&s.as_str()) => depth += 1,
+            Token::Symbol(s) if [")", "]", "}"].contains(&s.as_str()) => depth -= 1,
+            Token::Ident(keyword)
+                if depth == 0 && ["const", "let", "var"].contains(&keyword.as_str()) =>
+            {
+                let mut parser = Parser::new(tokens, position + 1);
+                // Destructuring patterns are not supported
+                let Ok(name) = parser.expect_name() else {
+                    continue;
                 };
+                if parser.is_symbol(":") {
+                    parser.position += 1;
+                    if parser.skip_type().is_err() {
+                        continue;
+                    }
+                }
+                if parser.expect_symbol("=").is_err() {
+                    continue;
+                }
 
-                (container["name"].to_owned(), container["value"].to_owned(), service_name)
-})
-            .collect();
+                match parser.parse_expression() {
+                    Ok(Expression::New(class, arguments))
+                        if class.to_string() == "pulumi.Config" =>
+                    {
+                        symbols.configs.insert(name, get_namespace(&arguments));
+                    }
+                    Ok(Expression::New(..)) => {}
+                    Ok(expression) => {
+                        if let Some(value) = resolve(&expression, &symbols) {
+                            symbols.values.insert(name, value);
+                        }
+                    }
+                    Err(e) => warn!("Declaration of `{}` cannot be parsed: {}", name, e),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    symbols
+}
 
-    let mut images: Vec<ContainerImageBluePrint> = vec![];
+#[derive(Debug, PartialEq)]
+struct ResourceCall {
+    variable: Option<String>,
+    arguments: Vec<Expression>,
+}
 
-    for (_image_name, image, service_name) in images_services {
-        let mut s = String::from("");
+// Target of `const name = new ...`, type annotations are allowed
+fn get_assigned_variable(tokens: &[Token], position: usize) -> Option<String> {
+    let mut index = position.checked_sub(1)?;
+    if tokens[index] != Token::Symbol("=".to_string()) {
+        return None;
+    }
 
-        for line in image.trim().lines() {
-            let parsed_line = parse_line(line);
+    while index > 0 {
+        index -= 1;
+        match &tokens[index] {
+            Token::Ident(keyword) if ["const", "let", "var"].contains(&keyword.as_str()) => {
+                return match tokens.get(index + 1) {
+                    Some(Token::Ident(name)) => Some(name.to_string()),
+                    _ => None,
+                };
+            }
+            Token::Symbol(s) if [";", "{", "}", "(", ")"].contains(&s.as_str()) => break,
+            _ => {}
+        }
+    }
 
-            s.push_str(&parsed_line);
+    // Plain assignment
+    match (
+        position.checked_sub(2).map(|i| &tokens[i]),
+        position.checked_sub(3).map(|i| &tokens[i]),
+    ) {
+        (Some(Token::Ident(name)), previous)
+            if previous != Some(&Token::Symbol(".".to_string())) =>
+        {
+            Some(name.to_string())
         }
+        _ => None,
+    }
+}
 
-        s = prune_output(s)
-            // Add custom behavior
-            .replace("imageName", "name");
+fn find_resources(tokens: &[Token], class: &str) -> Result<Vec<ResourceCall>, String> {
+    let mut resources = vec![];
 
-        let mut serialized: ContainerImageBluePrint = serde_json::from_str(&s).unwrap();
+    for (position, token) in tokens.iter().enumerate() {
+        if *token != Token::Ident("new".to_string()) {
+            continue;
+        }
 
-        if service_name.is_some() {
-            serialized.name = Some(service_name.clone().unwrap());
-            serialized.reference_name = Some(format!("{}.imageName", service_name.unwrap()));
+        let mut parser = Parser::new(tokens, position + 1);
+        // Class can be accessed from its package, eg: `azure_native.app.ContainerApp`
+        let is_resource = match parser.parse_callee() {
+            Ok(callee) => {
+                let callee = callee.to_string();
+                callee == class || callee.ends_with(&format!(".{}", class))
+            }
+            Err(_) => false,
+        };
+        if !is_resource || !parser.is_symbol("(") {
+            continue;
         }
 
-        images.push(serialized);
+        resources.push(ResourceCall {
+            variable: get_assigned_variable(tokens, position),
+            arguments: parser.parse_arguments()?,
+        });
     }
 
-    images
+    Ok(resources)
 }
 
-fn get_apps(input: &str) -> Vec<ContainerAppBluePrint> {
-    let container_app_services: Vec<(String, String)> = Regex::new(
-        r####"new app.ContainerApp\("(?P<name>.+)",( ?)(?P<value>\{(\n.+)+[^;s"\n.+])"####,
-    )
-    .unwrap()
-    .captures_iter(&input)
-    .map(|container| (container["name"].to_owned(), container["value"].to_owned()))
+fn get_images(tokens: &[Token], symbols: &Symbols) -> Result<Vec<ContainerImageBluePrint>, String> {
+    let images = [
+        find_resources(tokens, "docker.Image")?,
+        find_resources(tokens, "docker_build.Image")?,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|resource| {
+        // Images cannot be referenced without a variable
+        let variable = resource.variable?;
+        let properties = to_value(resource.arguments.get(1)?, symbols);
+
+        pulumi::image::parse_image(Some(variable), &properties)
+    })
     .collect();
 
-    let mut containers: Vec<ContainerAppBluePrint> = vec![];
+    Ok(images)
+}
+
+fn get_apps(tokens: &[Token], symbols: &Symbols) -> Result<Vec<ContainerAppBluePrint>, String> {
+    [
+        find_resources(tokens, "app.ContainerApp")?,
+        find_resources(tokens, "app.Job")?,
+    ]
+    .iter()
+    .flatten()
+    .map(|resource| {
+        let properties = resource
+            .arguments
+            .get(1)
+            .ok_or("Container app arguments need to be defined")?;
+
+        serde_json::from_value(to_value(properties, symbols)).map_err(|e| e.to_string())
+    })
+    .collect()
+}
+
+fn get_components(
+    tokens: &[Token],
+    symbols: &Symbols,
+) -> Result<Vec<DaprComponentBluePrint>, String> {
+    let components = find_resources(tokens, "app.DaprComponent")?
+        .iter()
+        .filter_map(|resource| {
+            let name = to_value(resource.arguments.first()?, symbols);
+            let properties = to_value(resource.arguments.get(1)?, symbols);
+
+            pulumi::parse_dapr_component(name.as_str().map(|name| name.to_string()), properties)
+        })
+        .collect();
+
+    Ok(components)
+}
+
+// Relative modules of `import ... from "./x"`, `export ... from "./x"`, `import "./x"` and `require("./x")`
+fn get_imports(tokens: &[Token]) -> Vec<String> {
+    (0..tokens.len())
+        .filter_map(|position| {
+            match (
+                &tokens[position],
+                tokens.get(position + 1),
+                tokens.get(position + 2),
+            ) {
+                (Token::Ident(keyword), Some(Token::String(path)), _)
+                    if keyword == "from" || keyword == "import" =>
+                {
+                    Some(path)
+                }
+                (Token::Ident(keyword), Some(Token::Symbol(s)), Some(Token::String(path)))
+                    if (keyword == "require" || keyword == "import") && s == "(" =>
+                {
+                    Some(path)
+                }
+                _ => None,
+            }
+        })
+        .filter(|path| path.starts_with('.'))
+        .map(|path| path.to_string())
+        .collect()
+}
+
+// Module file of an import, TypeScript allows to import `./x.js` from `./x.ts`
+fn resolve_module(folder: &Path, specifier: &str) -> Option<PathBuf> {
+    let path = folder.join(specifier);
+    let stem = path.with_extension("");
+    let mut candidates = vec![path.clone()];
 
-    for (_container_name, container) in container_app_services {
-        let mut s = String::from("");
+    for extension in ["ts", "tsx", "js", "mjs"] {
+        candidates.push(PathBuf::from(format!("{}.{}", path.display(), extension)));
+        candidates.push(stem.with_extension(extension));
+        candidates.push(path.join(format!("index.{}", extension)));
+    }
 
-        for line in container.trim().lines() {
-            let parsed_line = parse_line(line);
-            s.push_str(&parsed_line);
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+fn visit_module(path: &Path, visited: &mut Vec<PathBuf>, modules: &mut Vec<String>) {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if visited.contains(&canonical) {
+        return;
+    }
+    visited.push(canonical);
+
+    let tokens = match fs::read_to_string(path).map(|input| tokenize(&input)) {
+        Ok(Ok(tokens)) => tokens,
+        Ok(Err(e)) => {
+            warn!("Module {} cannot be parsed: {}", path.display(), e);
+            return;
+        }
+        Err(e) => {
+            warn!("Module {} cannot be read: {}", path.display(), e);
+            return;
         }
-        s = prune_output(s);
+    };
 
-        let serialized: ContainerAppBluePrint = serde_json::from_str(&s).unwrap();
+    let folder = path.parent().unwrap_or_else(|| Path::new("."));
+    for specifier in get_imports(&tokens) {
+        match resolve_module(folder, &specifier) {
+            Some(module) => {
+                visit_module(&module, visited, modules);
+                let module = module.to_string_lossy().to_string();
+                if !modules.contains(&module) {
+                    modules.push(module);
+                }
+            }
+            None => warn!(
+                "Module {} imported by {} not found",
+                specifier,
+                path.display()
+            ),
+        }
+    }
+}
 
-        containers.push(serialized);
+/***
+ * Relative modules imported by the program, followed recursively, dependencies come first
+ */
+pub fn find_modules(input: &str) -> Vec<String> {
+    let mut modules = vec![];
+    visit_module(Path::new(input), &mut vec![], &mut modules);
+
+    modules
+}
+
+// Imported names are replaced by their declaration name, eg: `import { a as b }` or `import * as ns`
+fn link_imports(tokens: Vec<Token>) -> Vec<Token> {
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    let mut namespaces: Vec<String> = vec![];
+
+    for (position, token) in tokens.iter().enumerate() {
+        if *token != Token::Ident("import".to_string()) {
+            continue;
+        }
+
+        let clause: Vec<&Token> = tokens[position + 1..]
+            .iter()
+            .take_while(|token| {
+                **token != Token::Ident("from".to_string()) && !matches!(token, Token::String(_))
+            })
+            .collect();
+        // Packages keep their namespace, eg: `import * as docker from "@pulumi/docker"`
+        let is_relative = matches!(
+            tokens.get(position + clause.len() + 2),
+            Some(Token::String(path)) if path.starts_with('.')
+        );
+        if !is_relative {
+            continue;
+        }
+
+        for window in clause.windows(3) {
+            match window {
+                [Token::Symbol(s), Token::Ident(keyword), Token::Ident(namespace)]
+                    if s == "*" && keyword == "as" =>
+                {
+                    namespaces.push(namespace.to_string())
+                }
+                [Token::Ident(name), Token::Ident(keyword), Token::Ident(alias)]
+                    if keyword == "as" =>
+                {
+                    aliases.insert(alias.to_string(), name.to_string());
+                }
+                _ => {}
+            }
+        }
     }
 
-    containers
+    let mut output = vec![];
+    let mut iterator = tokens.into_iter().peekable();
+    while let Some(token) = iterator.next() {
+        match token {
+            Token::Ident(name) if namespaces.contains(&name) => {
+                if iterator.peek() == Some(&Token::Symbol(".".to_string())) {
+                    iterator.next();
+                } else {
+                    output.push(Token::Ident(name));
+                }
+            }
+            Token::Ident(name) => {
+                output.push(Token::Ident(aliases.get(&name).cloned().unwrap_or(name)))
+            }
+            token => output.push(token),
+        }
+    }
+
+    output
 }
 
-pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String> {
-    let input = Regex::new(r"[^});](\n){2,}")
-        .unwrap()
-        .replace_all(input, "");
+pub fn deserialize(
+    input: &str,
+    modules: &[String],
+    config: &Config,
+) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let mut tokens = vec![];
+    // Imported modules are read first, their declarations are available to the program
+    for module in modules.iter().map(|module| module.as_str()).chain([input]) {
+        let module_tokens = tokenize(module).map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+        tokens.append(&mut link_imports(module_tokens));
+        tokens.push(Token::Symbol(";".to_string()));
+    }
 
-    let images = get_images(&input);
-    let apps = get_apps(&input);
+    let symbols = get_symbols(&tokens, config);
+    let images = get_images(&tokens, &symbols)?;
+    let apps = get_apps(&tokens, &symbols)?;
+    let components = get_components(&tokens, &symbols)?;
 
-    let services = pulumi::build_configuration(apps, images);
+    let services = pulumi::build_configuration(apps, images, components);
 
     match services {
         Some(val) => Ok(val),
@@ -152,52 +1317,199 @@ pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String
     }
 }
 
+#[cfg(test)]
 mod tests {
+    use serde_json::json;
+
     use crate::serializer::{
-        ConfigurationBluePrint, ContainerBluePrint, DaprBluePrint, IngressBluePrint,
-        TemplateBluePrint,
+        BuildContextBluePrint, ConfigurationBluePrint, ContainerBluePrint, DaprBluePrint,
+        IngressBluePrint, TemplateBluePrint,
     };
-    use std::panic;
 
     use super::*;
 
-    #[test]
-    fn test_parse_line() {
-        let output = parse_line("key: ");
-        assert_eq!("\"key\":", output);
-
-        let output = parse_line("key: value");
-        assert_eq!("\"key\":\"value\",", output);
-
-        let output = parse_line("\"key\":\"value\"");
-        assert_eq!("\"key\":\"value\",", output);
-
-        let output = parse_line("key:\"value\"");
-        assert_eq!("\"key\":\"value\",", output);
-
-        let output = parse_line("\"key\":value");
-        assert_eq!("\"key\":\"value\",", output);
-
-        let output = parse_line("\"key\":value,");
-        assert_eq!("\"key\":\"value\",", output);
+    fn parse_expression(input: &str) -> Result<Expression, String> {
+        let tokens = tokenize(input)?;
+        Parser::new(&tokens, 0).parse_expression()
+    }
 
-        let output = parse_line("\"key\":'value',");
-        assert_eq!("\"key\":\"value\",", output);
+    #[test]
+    fn test_tokenize() {
+        let output =
+            tokenize("const a = `${registry.loginServer}/remix:${{ a: 1 }.a}`; // comment");
+        let expected = vec![
+            Token::Ident("const".to_string()),
+            Token::Ident("a".to_string()),
+            Token::Symbol("=".to_string()),
+            Token::Template(vec![
+                Fragment::Expression("registry.loginServer".to_string()),
+                Fragment::Text("/remix:".to_string()),
+                Fragment::Expression("{ a: 1 }.a".to_string()),
+            ]),
+            Token::Symbol(";".to_string()),
+        ];
+        assert_eq!(Ok(expected), output);
+
+        let output = tokenize("x.replace(/a\\/b/g, '') / 2");
+        let expected = vec![
+            Token::Ident("x".to_string()),
+            Token::Symbol(".".to_string()),
+            Token::Ident("replace".to_string()),
+            Token::Symbol("(".to_string()),
+            Token::Ident("/a\\/b/g".to_string()),
+            Token::Symbol(",".to_string()),
+            Token::String("".to_string()),
+            Token::Symbol(")".to_string()),
+            Token::Symbol("/".to_string()),
+            Token::Number("2".to_string()),
+        ];
+        assert_eq!(Ok(expected), output);
+
+        let output = tokenize("'unterminated");
+        assert_eq!(Err("Unterminated string literal".to_string()), output);
+    }
 
-        let output = parse_line("\"key\":`value`,");
-        assert_eq!("\"key\":\"value\",", output);
+    #[test]
+    fn test_to_value() {
+        let expression = parse_expression(
+            r#"{ external: true, targetPort: 8000, "transport": 'auto', traffic: [{ weight: 100, }], }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json!({
+                "external": true,
+                "targetPort": 8000,
+                "transport": "auto",
+                "traffic": [{ "weight": 100 }]
+            }),
+            to_value(&expression, &Symbols::default())
+        );
+
+        let expression =
+            parse_expression("pulumi.interpolate`${registry.loginServer}/remix:v1`").unwrap();
+        assert_eq!(
+            json!("${registry.loginServer}/remix:v1"),
+            to_value(&expression, &Symbols::default())
+        );
+
+        let expression = parse_expression(
+            "{ ...defaults, port: Number(\"80\"), env: [...common, { name: \"A\" }], fqdn: app.configuration.apply((c: any) => c?.ingress?.fqdn) }",
+        )
+        .unwrap();
+        assert_eq!(
+            json!({
+                "port": 80.0,
+                "env": ["common", { "name": "A" }],
+                "fqdn": "app.configuration.apply(() => {...})"
+            }),
+            to_value(&expression, &Symbols::default())
+        );
+
+        let expression = parse_expression("(registry.name as string) + \"-app\"").unwrap();
+        assert_eq!(
+            json!("${registry.name}-app"),
+            to_value(&expression, &Symbols::default())
+        );
+
+        // Declared symbols
+        let symbols = Symbols {
+            values: HashMap::from([
+                ("port".to_string(), json!(3000)),
+                ("tag".to_string(), json!("v1")),
+                ("settings".to_string(), json!({ "apps": ["remix"] })),
+            ]),
+            ..Default::default()
+        };
+        let expression = parse_expression(
+            "{ targetPort: port, appPort: config.getNumber(\"port\") ?? port + 1, appId: settings.apps[0], image: `node:${tag}` }",
+        )
+        .unwrap();
+        assert_eq!(
+            json!({ "targetPort": 3000, "appPort": 3001, "appId": "remix", "image": "node:v1" }),
+            to_value(&expression, &symbols)
+        );
+
+        // Stack configuration
+        let symbols = Symbols {
+            configs: HashMap::from([("config".to_string(), None)]),
+            config: Config {
+                project: "capps".to_string(),
+                folder: PathBuf::new(),
+                values: HashMap::from([
+                    ("capps:port".to_string(), json!("8080")),
+                    ("azure-native:location".to_string(), json!("westeurope")),
+                ]),
+            },
+            ..Default::default()
+        };
+        let expression = parse_expression(
+            "{ port: config.requireNumber(\"port\"), replicas: config.getNumber(\"replicas\") ?? 1, location: new pulumi.Config(\"azure-native\").require(\"location\") }",
+        )
+        .unwrap();
+        assert_eq!(
+            json!({ "port": 8080, "replicas": 1, "location": "westeurope" }),
+            to_value(&expression, &symbols)
+        );
+    }
 
-        let output = parse_line("\"key\":\"node-12\",");
-        assert_eq!("\"key\":\"node-12\",", output);
+    #[test]
+    fn test_get_symbols() {
+        let data = r####"
+        const port = 3000;
+        let appId: string = "remix";
+        const image = `node:${port}`;
+        const registry = new azure_native.containerregistry.Registry("registry", {});
+        const config = new pulumi.Config();
+        function main() {
+            const port = 80;
+        }
+        "####;
 
-        let output = parse_line("\"key\":\"node:12\",");
-        assert_eq!("\"key\":\"node:12\",", output);
+        let output = get_symbols(&tokenize(data).unwrap(), &Config::default());
+        let expected = HashMap::from([
+            ("port".to_string(), json!(3000)),
+            ("appId".to_string(), json!("remix")),
+            ("image".to_string(), json!("node:3000")),
+        ]);
+        assert_eq!(expected, output.values);
+        assert_eq!(
+            HashMap::from([("config".to_string(), None)]),
+            output.configs
+        );
+    }
 
-        let output = parse_line("\"key\":\"node:12.4\",");
-        assert_eq!("\"key\":\"node:12.4\",", output);
+    #[test]
+    fn test_get_imports() {
+        let data = r####"
+        import * as pulumi from "@pulumi/pulumi";
+        import { remixImage } from "./images";
+        import "./registry";
+        export * from "../shared/config";
+        const apps = require("./apps");"####;
+
+        let output = get_imports(&tokenize(data).unwrap());
+        let expected = vec!["./images", "./registry", "../shared/config", "./apps"];
+        assert_eq!(expected, output);
+    }
 
-        let output = parse_line("\"key\":\"{}\"");
-        assert_eq!("", output);
+    #[test]
+    fn test_link_imports() {
+        let data = r####"
+        import * as docker from "@pulumi/docker";
+        import * as images from "./images";
+        import { servicePort as port } from "./config";
+        const image = images.remixImage.imageName + port;"####;
+
+        let output = link_imports(tokenize(data).unwrap());
+        let expected = tokenize(
+            r####"
+        import * as docker from "@pulumi/docker";
+        import * as images from "./images";
+        import { servicePort as servicePort } from "./config";
+        const image = remixImage.imageName + servicePort;"####,
+        )
+        .unwrap();
+        assert_eq!(expected, output);
     }
 
     #[test]
@@ -206,79 +1518,75 @@ mod tests {
         let data = r####"
         const test = new NoResource() {}
         "####;
-        let output = get_images(data);
+        let output = get_images(&tokenize(data).unwrap(), &Symbols::default());
         let expected: Vec<ContainerImageBluePrint> = vec![];
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
 
         // Valid resource name and context with reference
         let data = r####"
         const remixImage = new docker.Image("remix", {
             imageName: pulumi.interpolate`${registry.loginServer}/remix:v1`,
-            build: { 
+            build: {
                 context: "../frontend",
             },
         });"####;
 
-        let output = get_images(data);
+        let output = get_images(&tokenize(data).unwrap(), &Symbols::default());
         let expected = vec![ContainerImageBluePrint {
-            name: Some("remixImage".to_string()),
+            name: Some("${registry.loginServer}/remix:v1".to_string()),
             build: BuildContextBluePrint {
                 context: "../frontend".to_string(),
+                ..Default::default()
             },
-            reference_name: Some("remixImage.imageName".to_string()),
+            reference_name: Some("remixImage".to_string()),
         }];
 
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
 
-        // Valid resource name and context without reference
+        // Valid resource name and context without reference, on a single line
         let data = r####"
-        const remixImage = new docker.Image("remix", {
-            imageName: "node-18",
-            build: { 
-                context: "../frontend",
-            },
-        });"####;
+        export const remixImage: docker.Image = new docker.Image("remix", { imageName: "node-18", build: { context: "../frontend" } }); // comment"####;
 
-        let output = get_images(data);
+        let output = get_images(&tokenize(data).unwrap(), &Symbols::default());
         let expected = vec![ContainerImageBluePrint {
-            name: Some("remixImage".to_string()),
+            name: Some("node-18".to_string()),
             build: BuildContextBluePrint {
                 context: "../frontend".to_string(),
+                ..Default::default()
             },
-            reference_name: Some("remixImage.imageName".to_string()),
+            reference_name: Some("remixImage".to_string()),
         }];
 
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
 
-        // TODO
         // Valid resource name and context with direct string path
-        /*let data = r####"
+        let data = r####"
               const remixImage = new docker.Image("remix", {
                   imageName: "node-18",
-                  build: "",
+                  build: "../frontend",
               });"####;
 
-        let output = get_images(data);
+        let output = get_images(&tokenize(data).unwrap(), &Symbols::default());
         let expected = vec![ContainerImageBluePrint {
-            name: Some("remixImage".to_string()),
+            name: Some("node-18".to_string()),
             build: BuildContextBluePrint {
                 context: "../frontend".to_string(),
+                ..Default::default()
             },
-            reference_name: Some("remixImage.imageName".to_string()),
+            reference_name: Some("remixImage".to_string()),
         }];
 
-        assert_eq!(expected, output);*/
+        assert_eq!(Ok(expected), output);
 
-        // Invalid build context
+        // Without build context, the image is already pushed
         let data = r####"
                 const remixImage = new docker.Image("remix", {
                     imageName: "node-18",
-                    
-                });"####;
 
-        let expected = panic::catch_unwind(|| get_images(data));
+                });"####;
 
-        assert!(expected.is_err());
+        let output = get_images(&tokenize(data).unwrap(), &Symbols::default());
+        assert_eq!(Ok(vec![]), output);
     }
 
     #[test]
@@ -287,9 +1595,9 @@ mod tests {
         let data = r####"
                 const test = new NoResource() {}
                 "####;
-        let output = get_apps(data);
+        let output = get_apps(&tokenize(data).unwrap(), &Symbols::default());
         let expected: Vec<ContainerAppBluePrint> = vec![];
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
 
         // Valid resource
         let data = r####"
@@ -309,71 +1617,101 @@ mod tests {
                         containers: [{
                             name: "remix",
                             image: "node:12",
+                            command: ["npm"],
+                            args: ["run", "start", "--", "--port", 8000],
                         }],
                     },
                 });"####;
 
-        let output = get_apps(data);
+        let output = get_apps(&tokenize(data).unwrap(), &Symbols::default());
         let expected = vec![ContainerAppBluePrint {
             configuration: Some(ConfigurationBluePrint {
                 dapr: Some(DaprBluePrint {
                     app_id: Some("remix".to_string()),
                     app_port: Some(8000),
                     enabled: Some(true),
+                    app_protocol: None,
                 }),
                 ingress: Some(IngressBluePrint {
                     external: Some(true),
                     target_port: Some(8000),
                 }),
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
             }),
             template: Some(TemplateBluePrint {
                 containers: Some(vec![ContainerBluePrint {
                     image: "node:12".to_string(),
                     name: "remix".to_string(),
+                    env: None,
+                    command: Some(vec!["npm".to_string()]),
+                    args: Some(vec![
+                        "run".to_string(),
+                        "start".to_string(),
+                        "--".to_string(),
+                        "--port".to_string(),
+                        "8000".to_string(),
+                    ]),
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
                 }]),
+                scale: None,
+                volumes: None,
             }),
         }];
 
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
 
-        // Valid resource without ingress
+        // Valid resource without ingress, with comments and nested arrays
 
         let data = r####"
         const frontendApp = new app.ContainerApp("frontend", {
             configuration: {
-                dapr: {
-                    enabled: true,
-                    appPort: 8000,
-                    appId: "remix"
-                },
+                /* dapr sidecar */
+                dapr: { enabled: true, appPort: 8000, appId: "remix" },
+                registries: [{ server: registry.loginServer, identity: [[]] }],
             },
             template: {
                 containers: [{
-                    name: "remix",
+                    name: "remix", // frontend
                     image: "node:12",
                 }],
             },
-        });"####;
+        }, { dependsOn: [registry] });"####;
 
-        let output = get_apps(data);
+        let output = get_apps(&tokenize(data).unwrap(), &Symbols::default());
         let expected = vec![ContainerAppBluePrint {
             configuration: Some(ConfigurationBluePrint {
                 dapr: Some(DaprBluePrint {
                     app_id: Some("remix".to_string()),
                     app_port: Some(8000),
                     enabled: Some(true),
+                    app_protocol: None,
                 }),
                 ingress: None,
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
             }),
             template: Some(TemplateBluePrint {
                 containers: Some(vec![ContainerBluePrint {
                     image: "node:12".to_string(),
                     name: "remix".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
                 }]),
+                scale: None,
+                volumes: None,
             }),
         }];
 
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
 
         // Valid resource with empty configuration
         let data = r####"
@@ -387,18 +1725,26 @@ mod tests {
              },
          });"####;
 
-        let output = get_apps(data);
+        let output = get_apps(&tokenize(data).unwrap(), &Symbols::default());
         let expected = vec![ContainerAppBluePrint {
             configuration: None,
             template: Some(TemplateBluePrint {
                 containers: Some(vec![ContainerBluePrint {
                     image: "node:12".to_string(),
                     name: "remix".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
                 }]),
+                scale: None,
+                volumes: None,
             }),
         }];
 
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
 
         // Valid resource without configuration
         let data = r####"
@@ -408,22 +1754,38 @@ mod tests {
              template: {
                  containers: [{
                      name: "remix",
-                     image: "node:12",
+                     image: service1Image.imageName,
                  }],
              },
          });"####;
 
-        let output = get_apps(data);
+        let output = get_apps(&tokenize(data).unwrap(), &Symbols::default());
         let expected = vec![ContainerAppBluePrint {
             configuration: None,
             template: Some(TemplateBluePrint {
                 containers: Some(vec![ContainerBluePrint {
-                    image: "node:12".to_string(),
+                    image: "${service1Image.imageName}".to_string(),
                     name: "remix".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
                 }]),
+                scale: None,
+                volumes: None,
             }),
         }];
 
-        assert_eq!(expected, output);
+        assert_eq!(Ok(expected), output);
+
+        // Invalid resource
+        let data = r####"const frontendApp = new app.ContainerApp("frontend");"####;
+        let output = get_apps(&tokenize(data).unwrap(), &Symbols::default());
+        assert_eq!(
+            Err("Container app arguments need to be defined".to_string()),
+            output
+        );
     }
 }
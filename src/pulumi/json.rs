@@ -0,0 +1,394 @@
+use log::{error, warn};
+use serde_json::Value;
+
+use crate::pulumi;
+use crate::serializer::{
+    ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
+};
+
+const APP_TYPES: [&str; 2] = ["ContainerApp", "Job"];
+const DAPR_COMPONENT_TYPE: &str = "DaprComponent";
+const IMAGE_TYPES: [&str; 3] = [
+    "docker:index/image:Image",
+    "docker:Image",
+    "docker-build:index:Image",
+];
+// Key marking a secret value in a checkpoint
+const SECRET_SIGNATURE: &str = "4dabf18193072939515e22adb298388d";
+
+fn get_name(resource: &Value) -> Option<String> {
+    let urn = resource.get("urn")?.as_str()?;
+    urn.rsplit("::").next().map(|name| name.to_string())
+}
+
+fn get_type(resource: &Value) -> &str {
+    resource
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+}
+
+// Resources of the app module can also be created from an explicit API version, eg: `azure-native:app/v20230501:ContainerApp`
+fn is_app_resource(resource: &Value, classes: &[&str]) -> bool {
+    let resource_type = get_type(resource);
+    let Some((module, class)) = resource_type.rsplit_once(':') else {
+        return false;
+    };
+
+    (module == "azure-native:app" || module.starts_with("azure-native:app/"))
+        && classes.contains(&class)
+}
+
+/***
+ * Replace secret envelopes by their plaintext when the export was made with `--show-secrets`
+ */
+fn reveal_secrets(value: &Value) -> Value {
+    match value {
+        Value::Object(map) if map.contains_key(SECRET_SIGNATURE) => {
+            match map.get("plaintext").and_then(|p| p.as_str()) {
+                Some(plaintext) => serde_json::from_str(plaintext)
+                    .map(|v: Value| reveal_secrets(&v))
+                    .unwrap_or(Value::Null),
+                None => Value::Null,
+            }
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.to_string(), reveal_secrets(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(reveal_secrets).collect()),
+        value => value.to_owned(),
+    }
+}
+
+// Outputs are the deployed state, inputs are used when the resource has not been created yet
+fn get_properties(resource: &Value) -> Value {
+    let outputs = resource
+        .get("outputs")
+        .filter(|o| o.as_object().is_some_and(|o| !o.is_empty()));
+
+    match outputs.or_else(|| resource.get("inputs")) {
+        Some(properties) => reveal_secrets(properties),
+        None => Value::Null,
+    }
+}
+
+fn get_resources(deployment: &Value) -> Vec<&Value> {
+    deployment
+        .get("resources")
+        .and_then(|resources| resources.as_array())
+        .map(|resources| {
+            resources
+                .iter()
+                // Resources pending deletion are replaced by another one
+                .filter(|resource| resource.get("delete").and_then(|d| d.as_bool()) != Some(true))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn get_images(resources: &[&Value]) -> Vec<ContainerImageBluePrint> {
+    resources
+        .iter()
+        .filter(|resource| IMAGE_TYPES.contains(&get_type(resource)))
+        .filter_map(|resource| {
+            let mut properties = get_properties(resource);
+            // Build settings of `docker.Image` are inputs only
+            if let Some(build) = resource
+                .get("inputs")
+                .and_then(|inputs| inputs.get("build"))
+            {
+                if properties.get("build").is_none() {
+                    properties["build"] = build.to_owned();
+                }
+            }
+
+            pulumi::image::parse_image(get_name(resource), &properties)
+        })
+        .collect()
+}
+
+// Resolved image names are linked back to the image resource which built them
+fn link_images(app: &mut Value, resources: &[&Value]) {
+    let images: Vec<(String, String)> = resources
+        .iter()
+        .filter(|resource| IMAGE_TYPES.contains(&get_type(resource)))
+        .flat_map(|resource| {
+            let properties = get_properties(resource);
+            let name = get_name(resource).unwrap_or_default();
+            ["imageName", "repoDigest", "ref"]
+                .iter()
+                .filter_map(|key| properties.get(key)?.as_str().map(|r| r.to_string()))
+                .map(|reference| (reference, name.clone()))
+                .collect::<Vec<(String, String)>>()
+        })
+        .collect();
+
+    let containers = app
+        .pointer_mut("/template/containers")
+        .and_then(|containers| containers.as_array_mut());
+
+    for container in containers.into_iter().flatten() {
+        let image = container
+            .get("image")
+            .and_then(|i| i.as_str())
+            .unwrap_or_default();
+        if let Some((_, name)) = images.iter().find(|(reference, _)| reference == image) {
+            container["image"] = Value::String(format!("${{{}.imageName}}", name));
+        }
+    }
+}
+
+fn get_apps(resources: &[&Value]) -> Result<Vec<ContainerAppBluePrint>, String> {
+    resources
+        .iter()
+        .filter(|resource| is_app_resource(resource, &APP_TYPES))
+        .map(|resource| {
+            let mut properties = get_properties(resource);
+            if properties.is_null() {
+                return Err("Container app inputs or outputs need to be defined".to_string());
+            }
+            link_images(&mut properties, resources);
+
+            serde_json::from_value(properties).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+// Outputs name the component, inputs can only give its `componentName`
+fn get_components(resources: &[&Value]) -> Vec<DaprComponentBluePrint> {
+    resources
+        .iter()
+        .filter(|resource| is_app_resource(resource, &[DAPR_COMPONENT_TYPE]))
+        .filter_map(|resource| {
+            let properties = get_properties(resource);
+            let name = properties
+                .get("name")
+                .and_then(|name| name.as_str())
+                .map(|name| name.to_string())
+                .or_else(|| get_name(resource));
+
+            pulumi::parse_dapr_component(name, properties)
+        })
+        .collect()
+}
+
+pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let value: Value = match serde_json::from_str(input) {
+        Ok(v) => v,
+        Err(e) => {
+            error!("{}", e);
+            return Err(e.to_string());
+        }
+    };
+
+    let deployment = value
+        .get("deployment")
+        .ok_or("No deployment found, use `pulumi stack export`")?;
+    if deployment
+        .get("pending_operations")
+        .is_some_and(|p| p.as_array().is_some_and(|p| !p.is_empty()))
+    {
+        warn!("Stack has pending operations, some resources may not be up to date");
+    }
+
+    let resources = get_resources(deployment);
+    let images = get_images(&resources);
+    let apps = get_apps(&resources)?;
+    let components = get_components(&resources);
+
+    let services = pulumi::build_configuration(apps, images, components);
+
+    match services {
+        Some(val) => Ok(val),
+        None => Err("No container to deserialize".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::serializer::{
+        BuildContextBluePrint, ConfigurationBluePrint, ContainerBluePrint, DaprBluePrint,
+        IngressBluePrint, TemplateBluePrint,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_reveal_secrets() {
+        let value = json!({
+            "name": "pwd",
+            "value": {
+                "4dabf18193072939515e22adb298388d": "1b47061264138c4ac30d75fd1eb44270",
+                "plaintext": "\"secret\""
+            },
+            "other": {
+                "4dabf18193072939515e22adb298388d": "1b47061264138c4ac30d75fd1eb44270",
+                "ciphertext": "v1:abc"
+            }
+        });
+
+        let output = reveal_secrets(&value);
+
+        assert_eq!(
+            json!({ "name": "pwd", "value": "secret", "other": null }),
+            output
+        );
+    }
+
+    #[test]
+    fn test_get_images() {
+        let resources = [
+            json!({
+                "urn": "urn:pulumi:dev::project::docker:index/image:Image::remix",
+                "type": "docker:index/image:Image",
+                "inputs": { "build": { "context": "../frontend" }, "imageName": "capps.azurecr.io/remix:v1" },
+                "outputs": { "imageName": "capps.azurecr.io/remix:v1", "repoDigest": "capps.azurecr.io/remix@sha256:1" }
+            }),
+            json!({
+                "urn": "urn:pulumi:dev::project::docker:index/remoteImage:RemoteImage::nginx",
+                "type": "docker:index/remoteImage:RemoteImage",
+                "outputs": { "name": "nginx" }
+            }),
+        ];
+        let resources: Vec<&Value> = resources.iter().collect();
+
+        let output = get_images(&resources);
+
+        let expected = vec![ContainerImageBluePrint {
+            name: Some("capps.azurecr.io/remix:v1".to_string()),
+            build: BuildContextBluePrint {
+                context: "../frontend".to_string(),
+                ..Default::default()
+            },
+            reference_name: Some("remix".to_string()),
+        }];
+
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_get_apps() {
+        let resources = vec![
+            json!({
+                "urn": "urn:pulumi:dev::project::docker:index/image:Image::remix",
+                "type": "docker:index/image:Image",
+                "inputs": { "build": { "context": "../frontend" } },
+                "outputs": { "imageName": "capps.azurecr.io/remix:v1", "repoDigest": "capps.azurecr.io/remix@sha256:1" }
+            }),
+            json!({
+                "urn": "urn:pulumi:dev::project::azure-native:app:ContainerApp::frontend",
+                "type": "azure-native:app:ContainerApp",
+                "inputs": {},
+                "outputs": {
+                    "configuration": {
+                        "dapr": { "appId": "remix", "appPort": 8000, "enabled": true },
+                        "ingress": { "external": true, "targetPort": 8000, "fqdn": "frontend.azurecontainerapps.io" }
+                    },
+                    "template": {
+                        "containers": [{ "name": "remix", "image": "capps.azurecr.io/remix@sha256:1" }]
+                    }
+                }
+            }),
+            json!({
+                "urn": "urn:pulumi:dev::project::azure-native:app:ContainerApp::deleted",
+                "type": "azure-native:app:ContainerApp",
+                "delete": true
+            }),
+        ];
+
+        let output = get_apps(&get_resources(&json!({ "resources": resources })));
+
+        let expected = vec![ContainerAppBluePrint {
+            configuration: Some(ConfigurationBluePrint {
+                ingress: Some(IngressBluePrint {
+                    external: Some(true),
+                    target_port: Some(8000),
+                }),
+                dapr: Some(DaprBluePrint {
+                    app_id: Some("remix".to_string()),
+                    app_port: Some(8000),
+                    enabled: Some(true),
+                    app_protocol: None,
+                }),
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
+            }),
+            template: Some(TemplateBluePrint {
+                containers: Some(vec![ContainerBluePrint {
+                    name: "remix".to_string(),
+                    image: "${remix.imageName}".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
+                }]),
+                scale: None,
+                volumes: None,
+            }),
+        }];
+
+        assert_eq!(Ok(expected), output);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let output = deserialize(r#"{ "version": 3 }"#);
+        assert_eq!(
+            Err("No deployment found, use `pulumi stack export`".to_string()),
+            output
+        );
+
+        let stack = r#"{
+            "version": 3,
+            "deployment": {
+                "resources": [{
+                    "urn": "urn:pulumi:dev::project::azure-native:app/v20230501:ContainerApp::worker",
+                    "type": "azure-native:app/v20230501:ContainerApp",
+                    "inputs": {
+                        "template": { "containers": [{ "name": "worker", "image": "worker:v2" }] }
+                    }
+                }]
+            }
+        }"#;
+
+        let output = deserialize(stack).unwrap();
+        assert_eq!(1, output.len());
+        assert_eq!(Some("worker:v2".to_string()), output[0].image);
+
+        // Images without an urn have no name to be referenced by
+        let stack = r#"{
+            "version": 3,
+            "deployment": {
+                "resources": [{
+                    "type": "docker:index/image:Image",
+                    "inputs": { "build": { "context": "../worker" }, "imageName": "worker:v1" }
+                }, {
+                    "urn": "urn:pulumi:dev::project::docker:index/image:Image::api",
+                    "type": "docker:index/image:Image",
+                    "inputs": { "build": { "context": "../api" }, "imageName": "api:v1" }
+                }, {
+                    "urn": "urn:pulumi:dev::project::azure-native:app:ContainerApp::api",
+                    "type": "azure-native:app:ContainerApp",
+                    "inputs": {
+                        "template": { "containers": [{ "name": "api", "image": "api:v1" }] }
+                    }
+                }]
+            }
+        }"#;
+
+        let output = deserialize(stack).unwrap();
+        assert_eq!(1, output.len());
+        assert_eq!(
+            Some("../api".to_string()),
+            output[0].build.as_ref().map(|build| build.context.clone())
+        );
+    }
+}
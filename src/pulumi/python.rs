@@ -0,0 +1,1188 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use log::{error, warn};
+use serde_json::{Map, Value};
+
+use crate::pulumi;
+use crate::serializer::{
+    ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    // Formatted string, replacement fields are kept as written
+    FString(String),
+    Number(String),
+    Symbol(String),
+    // Only emitted outside of brackets, where a new line ends a statement
+    Newline,
+}
+
+const SYMBOLS: [&str; 10] = ["**", "//", "==", "!=", "<=", ">=", "->", ":=", "+=", "-="];
+
+fn read_string(chars: &[char], position: &mut usize, is_raw: bool) -> Result<String, String> {
+    let quote = chars[*position];
+    let is_triple =
+        chars.get(*position + 1) == Some(&quote) && chars.get(*position + 2) == Some(&quote);
+    let delimiter = if is_triple { 3 } else { 1 };
+    let mut output = String::new();
+    *position += delimiter;
+
+    while *position < chars.len() {
+        let c = chars[*position];
+
+        if c == quote && (0..delimiter).all(|i| chars.get(*position + i) == Some(&quote)) {
+            *position += delimiter;
+            return Ok(output);
+        }
+        *position += 1;
+
+        if c == '\\' && !is_raw {
+            let escaped = chars.get(*position).copied().unwrap_or_default();
+            *position += 1;
+            match escaped {
+                'n' => output.push('\n'),
+                't' => output.push('\t'),
+                'r' => output.push('\r'),
+                // Line continuation
+                '\n' => {}
+                c => output.push(c),
+            }
+        } else if c == '\n' && !is_triple {
+            break;
+        } else {
+            output.push(c);
+        }
+    }
+
+    Err("Unterminated string literal".to_string())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+    // Implicit line joining inside brackets
+    let mut depth = 0;
+
+    while position < chars.len() {
+        let c = chars[position];
+
+        if c == '\n' {
+            if depth == 0 && tokens.last().is_some_and(|t| *t != Token::Newline) {
+                tokens.push(Token::Newline);
+            }
+            position += 1;
+        } else if c.is_whitespace() {
+            position += 1;
+        } else if c == '\\' && chars.get(position + 1) == Some(&'\n') {
+            position += 2;
+        } else if c == '#' {
+            while position < chars.len() && chars[position] != '\n' {
+                position += 1;
+            }
+        } else if c == '"' || c == '\'' {
+            let value = read_string(&chars, &mut position, false)?;
+            // Adjacent literals are concatenated
+            match tokens.last_mut() {
+                Some(Token::String(previous)) => previous.push_str(&value),
+                _ => tokens.push(Token::String(value)),
+            }
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(position + 1).is_some_and(|n| n.is_ascii_digit()))
+        {
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric()
+                    || chars[position] == '.'
+                    || chars[position] == '_')
+            {
+                position += 1;
+            }
+            tokens.push(Token::Number(chars[start..position].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = position;
+            while position < chars.len()
+                && (chars[position].is_alphanumeric() || chars[position] == '_')
+            {
+                position += 1;
+            }
+            let name: String = chars[start..position].iter().collect();
+            let prefix = name.to_lowercase();
+            let is_prefix = ["f", "r", "b", "u", "rb", "br", "fr", "rf"].contains(&prefix.as_str());
+
+            if is_prefix && matches!(chars.get(position), Some('"' | '\'')) {
+                let value = read_string(&chars, &mut position, prefix.contains('r'))?;
+                if prefix.contains('f') {
+                    tokens.push(Token::FString(value));
+                } else {
+                    tokens.push(Token::String(value));
+                }
+            } else {
+                tokens.push(Token::Ident(name));
+            }
+        } else {
+            let rest: String = chars[position..chars.len().min(position + 2)]
+                .iter()
+                .collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(*symbol))
+                .map(|symbol| symbol.to_string())
+                .unwrap_or_else(|| c.to_string());
+
+            match symbol.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ => {}
+            }
+            position += symbol.chars().count();
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Argument {
+    Positional(Expression),
+    Keyword(String, Expression),
+    // `*args` and `**kwargs` cannot be resolved
+    Unpacked(String, Expression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    String(String),
+    FString(String),
+    Number(String),
+    Name(String),
+    // Lambdas and comprehensions are kept as a placeholder, they are never evaluated
+    Skipped(String),
+    Attribute(Box<Expression>, String),
+    Call(Box<Expression>, Vec<Argument>),
+    Subscript(Box<Expression>, Box<Expression>),
+    List(Vec<Expression>),
+    Tuple(Vec<Expression>),
+    Dict(Vec<(Expression, Expression)>),
+    Unary(String, Box<Expression>),
+    Binary(Box<Expression>, String, Box<Expression>),
+    Conditional(Box<Expression>, Box<Expression>, Box<Expression>),
+}
+
+impl fmt::Display for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Argument::Positional(value) => write!(f, "{}", value),
+            Argument::Keyword(name, value) => write!(f, "{}={}", name, value),
+            Argument::Unpacked(operator, value) => write!(f, "{}{}", operator, value),
+        }
+    }
+}
+
+fn join<T: fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::String(value) => write!(f, "{:?}", value),
+            Expression::FString(value) => write!(f, "f{:?}", value),
+            Expression::Number(value) | Expression::Name(value) | Expression::Skipped(value) => {
+                write!(f, "{}", value)
+            }
+            Expression::Attribute(value, attribute) => write!(f, "{}.{}", value, attribute),
+            Expression::Call(callee, arguments) => write!(f, "{}({})", callee, join(arguments)),
+            Expression::Subscript(value, index) => write!(f, "{}[{}]", value, index),
+            Expression::List(values) => write!(f, "[{}]", join(values)),
+            Expression::Tuple(values) => write!(f, "({})", join(values)),
+            Expression::Dict(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            Expression::Unary(operator, value) if operator == "not" => write!(f, "not {}", value),
+            Expression::Unary(operator, value) => write!(f, "{}{}", operator, value),
+            Expression::Binary(left, operator, right) => {
+                write!(f, "{} {} {}", left, operator, right)
+            }
+            Expression::Conditional(value, condition, alternative) => {
+                write!(f, "{} if {} else {}", value, condition, alternative)
+            }
+        }
+    }
+}
+
+fn get_operator(token: &Token) -> Option<(String, u8)> {
+    let operator = match token {
+        Token::Ident(name) => name.as_str(),
+        Token::Symbol(symbol) => symbol.as_str(),
+        _ => return None,
+    };
+
+    let precedence = match operator {
+        "or" => 1,
+        "and" => 2,
+        "==" | "!=" | "<" | "<=" | ">" | ">=" | "in" | "is" => 3,
+        "|" => 4,
+        "&" => 5,
+        "+" | "-" => 6,
+        "*" | "/" | "//" | "%" => 7,
+        "**" => 8,
+        _ => return None,
+    };
+
+    Some((operator.to_string(), precedence))
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token], position: usize) -> Parser<'a> {
+        Parser { tokens, position }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if s == symbol)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(name)) if name == keyword)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == symbol => Ok(()),
+            token => Err(format!("Expected `{}`, found {:?}", symbol, token)),
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        if self.is_keyword("lambda") {
+            return self.skip_lambda();
+        }
+
+        let value = self.parse_binary(1)?;
+        if !self.is_keyword("if") {
+            return Ok(value);
+        }
+
+        self.position += 1;
+        let condition = self.parse_binary(1)?;
+        match self.next() {
+            Some(Token::Ident(name)) if name == "else" => {}
+            token => return Err(format!("Expected `else`, found {:?}", token)),
+        }
+        let alternative = self.parse_expression()?;
+
+        Ok(Expression::Conditional(
+            Box::new(value),
+            Box::new(condition),
+            Box::new(alternative),
+        ))
+    }
+
+    fn parse_binary(&mut self, minimum: u8) -> Result<Expression, String> {
+        let mut left = self.parse_unary()?;
+
+        while let Some((operator, precedence)) = self.peek().and_then(get_operator) {
+            if precedence < minimum {
+                break;
+            }
+            self.position += 1;
+            let right = self.parse_binary(precedence + 1)?;
+            left = Expression::Binary(Box::new(left), operator, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        let operator = match self.peek() {
+            Some(Token::Symbol(s)) if ["-", "+", "~"].contains(&s.as_str()) => s.to_string(),
+            Some(Token::Ident(name)) if name == "not" => name.to_string(),
+            _ => {
+                let primary = self.parse_primary()?;
+                return self.parse_postfix(primary);
+            }
+        };
+
+        self.position += 1;
+        Ok(Expression::Unary(operator, Box::new(self.parse_unary()?)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::String(value)) => Ok(Expression::String(value)),
+            Some(Token::FString(value)) => Ok(Expression::FString(value)),
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::Ident(name)) => Ok(Expression::Name(name)),
+            Some(Token::Symbol(s)) if s == "(" => {
+                let values = self.parse_sequence(")")?;
+                match values {
+                    Some((mut values, false)) if values.len() == 1 => Ok(values.remove(0)),
+                    Some((values, _)) => Ok(Expression::Tuple(values)),
+                    None => Ok(Expression::Skipped("(...)".to_string())),
+                }
+            }
+            Some(Token::Symbol(s)) if s == "[" => match self.parse_sequence("]")? {
+                Some((values, _)) => Ok(Expression::List(values)),
+                None => Ok(Expression::Skipped("[...]".to_string())),
+            },
+            Some(Token::Symbol(s)) if s == "{" => self.parse_dict(),
+            token => Err(format!("Unexpected token {:?}", token)),
+        }
+    }
+
+    /***
+     * Comma separated values up to the closing symbol, None for a comprehension
+     * The flag is set when a trailing comma makes a single value a tuple
+     */
+    fn parse_sequence(&mut self, closing: &str) -> Result<Option<(Vec<Expression>, bool)>, String> {
+        let mut values = vec![];
+        let mut has_comma = false;
+
+        while !self.is_symbol(closing) {
+            if self.is_symbol("*") {
+                self.position += 1;
+            }
+            values.push(self.parse_expression()?);
+
+            if self.is_keyword("for") {
+                self.skip_until(closing)?;
+                return Ok(None);
+            }
+            if !self.is_symbol(closing) {
+                self.expect_symbol(",")?;
+                has_comma = true;
+            }
+        }
+        self.position += 1;
+
+        Ok(Some((values, has_comma)))
+    }
+
+    fn parse_dict(&mut self) -> Result<Expression, String> {
+        let mut entries = vec![];
+
+        while !self.is_symbol("}") {
+            if self.is_symbol("**") {
+                // Unpacked mappings cannot be resolved
+                self.position += 1;
+                self.parse_expression()?;
+            } else {
+                let key = self.parse_expression()?;
+                if self.is_keyword("for") {
+                    self.skip_until("}")?;
+                    return Ok(Expression::Skipped("{...}".to_string()));
+                }
+                self.expect_symbol(":")?;
+                entries.push((key, self.parse_expression()?));
+
+                if self.is_keyword("for") {
+                    self.skip_until("}")?;
+                    return Ok(Expression::Skipped("{...}".to_string()));
+                }
+            }
+            if !self.is_symbol("}") {
+                self.expect_symbol(",")?;
+            }
+        }
+        self.position += 1;
+
+        Ok(Expression::Dict(entries))
+    }
+
+    fn parse_postfix(&mut self, mut expression: Expression) -> Result<Expression, String> {
+        loop {
+            if self.is_symbol(".") {
+                self.position += 1;
+                match self.next() {
+                    Some(Token::Ident(name)) => {
+                        expression = Expression::Attribute(Box::new(expression), name)
+                    }
+                    token => return Err(format!("Expected attribute, found {:?}", token)),
+                }
+            } else if self.is_symbol("(") {
+                expression = Expression::Call(Box::new(expression), self.parse_arguments()?);
+            } else if self.is_symbol("[") {
+                self.position += 1;
+                let index = self.parse_expression()?;
+                // Slices are not evaluated
+                if !self.is_symbol("]") {
+                    self.skip_until("]")?;
+                } else {
+                    self.position += 1;
+                }
+                expression = Expression::Subscript(Box::new(expression), Box::new(index));
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<Argument>, String> {
+        self.expect_symbol("(")?;
+        let mut arguments = vec![];
+
+        while !self.is_symbol(")") {
+            let is_keyword = matches!(self.peek(), Some(Token::Ident(_)))
+                && matches!(self.tokens.get(self.position + 1), Some(Token::Symbol(s)) if s == "=");
+
+            if is_keyword {
+                let name = match self.next() {
+                    Some(Token::Ident(name)) => name,
+                    _ => unreachable!(),
+                };
+                self.position += 1;
+                arguments.push(Argument::Keyword(name, self.parse_expression()?));
+            } else if self.is_symbol("*") || self.is_symbol("**") {
+                let operator = match self.next() {
+                    Some(Token::Symbol(s)) => s,
+                    _ => unreachable!(),
+                };
+                arguments.push(Argument::Unpacked(operator, self.parse_expression()?));
+            } else {
+                arguments.push(Argument::Positional(self.parse_expression()?));
+                if self.is_keyword("for") {
+                    // Generator expression as the only argument
+                    self.skip_until(")")?;
+                    return Ok(arguments);
+                }
+            }
+
+            if !self.is_symbol(")") {
+                self.expect_symbol(",")?;
+            }
+        }
+        self.position += 1;
+
+        Ok(arguments)
+    }
+
+    // Skip tokens up to the closing symbol of the current bracket, which is consumed
+    fn skip_until(&mut self, closing: &str) -> Result<(), String> {
+        let mut depth = 0;
+
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol(s) if ["(", "[", "{"].contains(&s.as_str()) => depth += 1,
+                Token::Symbol(s) if depth == 0 && s == closing => return Ok(()),
+                Token::Symbol(s) if [")", "]", "}"].contains(&s.as_str()) => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Err(format!("Expected `{}`", closing))
+    }
+
+    fn skip_lambda(&mut self) -> Result<Expression, String> {
+        while !self.is_symbol(":") {
+            if self.next().is_none() {
+                return Err("Expected `:` after lambda parameters".to_string());
+            }
+        }
+        self.position += 1;
+        self.parse_expression()?;
+
+        Ok(Expression::Skipped("lambda: ...".to_string()))
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Null => "".to_string(),
+        v => v.to_string(),
+    }
+}
+
+// Same convention as the serde camelCase renames of the blueprints
+fn to_camel_case(name: &str) -> String {
+    let mut output = String::new();
+    let mut is_upper = false;
+
+    for c in name.trim_matches('_').chars() {
+        if c == '_' {
+            is_upper = true;
+        } else if is_upper {
+            output.extend(c.to_uppercase());
+            is_upper = false;
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+// Replacement fields are kept as references, `{{` and `}}` are escapes
+fn format_fstring(value: &str) -> String {
+    let mut output = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let field: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let field = field.split([':', '!']).next().unwrap_or_default().trim();
+                let (object, attribute) = field.rsplit_once('.').unwrap_or((field, ""));
+                if attribute.is_empty() {
+                    output.push_str(&format!("${{{}}}", object));
+                } else {
+                    output.push_str(&format!("${{{}.{}}}", object, to_camel_case(attribute)));
+                }
+            }
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn format(arguments: &[Value]) -> Option<Value> {
+    let template = arguments.first()?.as_str()?;
+    let mut output = String::new();
+    let mut chars = template.chars().peekable();
+    let mut index = 0;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let field: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let position = match field.split(':').next()?.parse::<usize>() {
+                    Ok(position) => position,
+                    Err(_) => {
+                        index += 1;
+                        index - 1
+                    }
+                };
+                output.push_str(&value_to_string(arguments.get(position + 1)?));
+            }
+            c => output.push(c),
+        }
+    }
+
+    Some(Value::String(output))
+}
+
+/***
+ * Pulumi SDK and builtin functions, None when it cannot be resolved locally
+ */
+fn call_function(name: &str, arguments: &[Value]) -> Option<Value> {
+    match name {
+        "pulumi.Output.concat" | "Output.concat" => Some(Value::String(
+            arguments.iter().map(value_to_string).collect(),
+        )),
+        "pulumi.Output.format" | "Output.format" => format(arguments),
+        "pulumi.Output.from_input" | "Output.from_input" | "pulumi.Output.secret" => {
+            arguments.first().cloned()
+        }
+        "str" => arguments.first().map(|v| Value::String(value_to_string(v))),
+        "int" => value_to_string(arguments.first()?)
+            .parse::<i64>()
+            .ok()
+            .map(Value::from),
+        _ => None,
+    }
+}
+
+fn to_value(expression: &Expression) -> Value {
+    match expression {
+        Expression::String(value) => Value::String(value.to_string()),
+        Expression::FString(value) => Value::String(format_fstring(value)),
+        Expression::Number(value) => match value.replace('_', "").parse::<i64>() {
+            Ok(number) => Value::from(number),
+            Err(_) => value
+                .parse::<f64>()
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::String(value.to_string())),
+        },
+        Expression::Name(name) if name == "True" => Value::Bool(true),
+        Expression::Name(name) if name == "False" => Value::Bool(false),
+        Expression::Name(name) if name == "None" => Value::Null,
+        // Output of another resource, kept as a reference like other languages
+        Expression::Attribute(object, attribute) if matches!(**object, Expression::Name(_)) => {
+            Value::String(format!("${{{}.{}}}", object, to_camel_case(attribute)))
+        }
+        Expression::Call(callee, arguments) => {
+            let is_keyword_only = !arguments.is_empty()
+                && arguments
+                    .iter()
+                    .all(|argument| matches!(argument, Argument::Keyword(..)));
+
+            // Input types such as `app.IngressArgs(...)` only take keyword arguments
+            if is_keyword_only {
+                let mut map = Map::new();
+                for argument in arguments {
+                    if let Argument::Keyword(name, value) = argument {
+                        map.insert(to_camel_case(name), to_value(value));
+                    }
+                }
+                return Value::Object(map);
+            }
+
+            let values: Vec<Value> = arguments
+                .iter()
+                .filter_map(|argument| match argument {
+                    Argument::Positional(value) => Some(to_value(value)),
+                    _ => None,
+                })
+                .collect();
+
+            call_function(&callee.to_string(), &values)
+                .unwrap_or_else(|| Value::String(expression.to_string()))
+        }
+        Expression::List(values) | Expression::Tuple(values) => {
+            Value::Array(values.iter().map(to_value).collect())
+        }
+        Expression::Dict(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                map.insert(
+                    to_camel_case(&value_to_string(&to_value(key))),
+                    to_value(value),
+                );
+            }
+            Value::Object(map)
+        }
+        Expression::Binary(left, operator, right) if operator == "+" => {
+            match (to_value(left), to_value(right)) {
+                (Value::String(left), Value::String(right)) => Value::String(left + &right),
+                (Value::Array(mut left), Value::Array(right)) => {
+                    left.extend(right);
+                    Value::Array(left)
+                }
+                (Value::Number(left), Value::Number(right)) => {
+                    match (left.as_i64(), right.as_i64()) {
+                        (Some(left), Some(right)) => Value::from(left + right),
+                        _ => Value::String(expression.to_string()),
+                    }
+                }
+                _ => Value::String(expression.to_string()),
+            }
+        }
+        expression => Value::String(expression.to_string()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ResourceCall {
+    variable: Option<String>,
+    arguments: Vec<Argument>,
+}
+
+// Target of `name = ...` when the call starts the statement
+fn get_assigned_variable(tokens: &[Token], position: usize) -> Option<String> {
+    let index = position.checked_sub(2)?;
+    let is_statement = index == 0 || tokens[index - 1] == Token::Newline;
+
+    match (&tokens[index], &tokens[index + 1]) {
+        (Token::Ident(name), Token::Symbol(s)) if is_statement && s == "=" && name != "_" => {
+            Some(name.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn find_resources(
+    tokens: &[Token],
+    module: &str,
+    class: &str,
+) -> Result<Vec<ResourceCall>, String> {
+    let mut resources = vec![];
+
+    for (position, window) in tokens.windows(4).enumerate() {
+        let is_call = window[0] == Token::Ident(module.to_string())
+            && window[1] == Token::Symbol(".".to_string())
+            && window[2] == Token::Ident(class.to_string())
+            && window[3] == Token::Symbol("(".to_string());
+        if !is_call {
+            continue;
+        }
+
+        // Module can be accessed from its package, eg: `azure_native.app`
+        let start = match position.checked_sub(2).map(|i| &tokens[i..position]) {
+            Some([Token::Ident(_), Token::Symbol(s)]) if s == "." => position - 2,
+            _ => position,
+        };
+
+        let mut parser = Parser::new(tokens, position + 3);
+        resources.push(ResourceCall {
+            variable: get_assigned_variable(tokens, start),
+            arguments: parser.parse_arguments()?,
+        });
+    }
+
+    Ok(resources)
+}
+
+// Keyword arguments of a resource, the first positional one is the resource name
+fn get_properties(resource: &ResourceCall) -> Value {
+    let mut map = Map::new();
+
+    for argument in &resource.arguments {
+        if let Argument::Keyword(name, value) = argument {
+            map.insert(to_camel_case(name), to_value(value));
+        }
+    }
+
+    // Arguments can also be given as a single `args` object
+    match map.remove("args") {
+        Some(Value::Object(args)) => Value::Object(args.into_iter().chain(map).collect()),
+        _ => Value::Object(map),
+    }
+}
+
+fn get_images(tokens: &[Token]) -> Result<Vec<ContainerImageBluePrint>, String> {
+    let images = [
+        find_resources(tokens, "docker", "Image")?,
+        find_resources(tokens, "docker_build", "Image")?,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|resource| {
+        // Images cannot be referenced without a variable
+        let variable = resource.variable.clone()?;
+
+        pulumi::image::parse_image(Some(variable), &get_properties(&resource))
+    })
+    .collect();
+
+    Ok(images)
+}
+
+fn get_apps(tokens: &[Token]) -> Result<Vec<ContainerAppBluePrint>, String> {
+    [
+        find_resources(tokens, "app", "ContainerApp")?,
+        find_resources(tokens, "app", "Job")?,
+    ]
+    .iter()
+    .flatten()
+    .map(|resource| serde_json::from_value(get_properties(resource)).map_err(|e| e.to_string()))
+    .collect()
+}
+
+fn get_components(tokens: &[Token]) -> Result<Vec<DaprComponentBluePrint>, String> {
+    let components = find_resources(tokens, "app", "DaprComponent")?
+        .iter()
+        .filter_map(|resource| {
+            let name = resource
+                .arguments
+                .iter()
+                .find_map(|argument| match argument {
+                    Argument::Positional(name) => to_value(name).as_str().map(|n| n.to_string()),
+                    _ => None,
+                });
+
+            pulumi::parse_dapr_component(name, get_properties(resource))
+        })
+        .collect();
+
+    Ok(components)
+}
+
+/***
+ * Local modules of `import a.b` and `from .a import b` statements, with the names imported from them
+ */
+fn get_imports(tokens: &[Token]) -> Vec<(String, Vec<String>)> {
+    let mut imports = vec![];
+
+    for (position, token) in tokens.iter().enumerate() {
+        let is_statement = position == 0 || tokens[position - 1] == Token::Newline;
+        let Token::Ident(keyword) = token else {
+            continue;
+        };
+        if !is_statement || (keyword != "import" && keyword != "from") {
+            continue;
+        }
+
+        let statement: Vec<&Token> = tokens[position + 1..]
+            .iter()
+            .take_while(|token| **token != Token::Newline)
+            .collect();
+        // Names are separated by commas, aliases are ignored
+        let names = |tokens: &[&Token]| -> Vec<String> {
+            tokens
+                .split(|token| **token == Token::Symbol(",".to_string()))
+                .map(|name| {
+                    name.iter()
+                        .take_while(|token| **token != &Token::Ident("as".to_string()))
+                        .filter_map(|token| match token {
+                            Token::Ident(name) => Some(name.to_string()),
+                            Token::Symbol(s) if s == "." => Some(s.to_string()),
+                            _ => None,
+                        })
+                        .collect::<String>()
+                })
+                .filter(|name| !name.is_empty())
+                .collect()
+        };
+
+        match keyword.as_str() {
+            "import" => {
+                for module in names(&statement) {
+                    imports.push((module, vec![]));
+                }
+            }
+            _ => {
+                let Some(separator) = statement
+                    .iter()
+                    .position(|token| **token == Token::Ident("import".to_string()))
+                else {
+                    continue;
+                };
+                let module = names(&statement[..separator]).concat();
+                imports.push((module, names(&statement[separator + 1..])));
+            }
+        }
+    }
+
+    imports
+}
+
+// Module file of an import, relative imports start from the folder of the module and absolute ones from the program
+fn resolve_module(root: &Path, folder: &Path, module: &str) -> Option<PathBuf> {
+    let parents = module.chars().take_while(|c| *c == '.').count();
+    let mut base = match parents {
+        0 => root.to_path_buf(),
+        _ => folder.to_path_buf(),
+    };
+    for _ in 1..parents {
+        base = base.parent()?.to_path_buf();
+    }
+    let path = module[parents..]
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .fold(base, |path, part| path.join(part));
+
+    [path.with_extension("py"), path.join("__init__.py")]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
+
+fn visit_module(root: &Path, path: &Path, visited: &mut Vec<PathBuf>, modules: &mut Vec<String>) {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if visited.contains(&canonical) {
+        return;
+    }
+    visited.push(canonical);
+
+    let tokens = match fs::read_to_string(path).map(|input| tokenize(&input)) {
+        Ok(Ok(tokens)) => tokens,
+        Ok(Err(e)) => {
+            warn!("Module {} cannot be parsed: {}", path.display(), e);
+            return;
+        }
+        Err(e) => {
+            warn!("Module {} cannot be read: {}", path.display(), e);
+            return;
+        }
+    };
+
+    let folder = path.parent().unwrap_or_else(|| Path::new("."));
+    for (module, names) in get_imports(&tokens) {
+        // Packages such as `pulumi_azure_native` are not part of the program
+        let submodules = names.iter().map(|name| match module.ends_with('.') {
+            true => format!("{}{}", module, name),
+            false => format!("{}.{}", module, name),
+        });
+        for module in [module.clone()].into_iter().chain(submodules) {
+            if let Some(module) = resolve_module(root, folder, &module) {
+                visit_module(root, &module, visited, modules);
+                let module = module.to_string_lossy().to_string();
+                if !modules.contains(&module) {
+                    modules.push(module);
+                }
+            }
+        }
+    }
+}
+
+/***
+ * Local modules imported by the program, followed recursively, dependencies come first
+ */
+pub fn find_modules(input: &str) -> Vec<String> {
+    let path = Path::new(input);
+    let root = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut modules = vec![];
+    visit_module(root, path, &mut vec![], &mut modules);
+
+    modules
+}
+
+pub fn deserialize(
+    input: &str,
+    modules: &[String],
+) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let mut images = vec![];
+    let mut apps = vec![];
+    let mut components = vec![];
+    // Each file is parsed on its own, resources of every file are built together
+    for module in [input]
+        .into_iter()
+        .chain(modules.iter().map(|module| module.as_str()))
+    {
+        let tokens = tokenize(module).map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+
+        images.append(&mut get_images(&tokens)?);
+        apps.append(&mut get_apps(&tokens)?);
+        components.append(&mut get_components(&tokens)?);
+    }
+
+    let services = pulumi::build_configuration(apps, images, components);
+
+    match services {
+        Some(val) => Ok(val),
+        None => Err("No container to deserialize".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::serializer::{
+        BuildContextBluePrint, ConfigurationBluePrint, ContainerBluePrint, DaprBluePrint,
+        IngressBluePrint, TemplateBluePrint,
+    };
+
+    use super::*;
+
+    fn parse_expression(input: &str) -> Result<Expression, String> {
+        let tokens = tokenize(input)?;
+        Parser::new(&tokens, 0).parse_expression()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let output = tokenize("app = app.ContainerApp(\n    \"app\", # comment\n)\nx = f'{a}' 'b'");
+        let expected = vec![
+            Token::Ident("app".to_string()),
+            Token::Symbol("=".to_string()),
+            Token::Ident("app".to_string()),
+            Token::Symbol(".".to_string()),
+            Token::Ident("ContainerApp".to_string()),
+            Token::Symbol("(".to_string()),
+            Token::String("app".to_string()),
+            Token::Symbol(",".to_string()),
+            Token::Symbol(")".to_string()),
+            Token::Newline,
+            Token::Ident("x".to_string()),
+            Token::Symbol("=".to_string()),
+            Token::FString("{a}".to_string()),
+            Token::String("b".to_string()),
+        ];
+        assert_eq!(Ok(expected), output);
+
+        let output = tokenize("'''multi\nline''' \"a\" 'b'");
+        assert_eq!(Ok(vec![Token::String("multi\nlineab".to_string())]), output);
+
+        let output = tokenize("\"unterminated");
+        assert_eq!(Err("Unterminated string literal".to_string()), output);
+    }
+
+    #[test]
+    fn test_to_value() {
+        let expression = parse_expression(
+            "app.IngressArgs(external=True, target_port=80, transport=app.IngressTransportMethod.AUTO)",
+        )
+        .unwrap();
+        assert_eq!(
+            json!({
+                "external": true,
+                "targetPort": 80,
+                "transport": "app.IngressTransportMethod.AUTO"
+            }),
+            to_value(&expression)
+        );
+
+        let expression = parse_expression(
+            r#"{"containers": [{"name": "myapp", "image": my_image.image_name}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json!({ "containers": [{ "name": "myapp", "image": "${my_image.imageName}" }] }),
+            to_value(&expression)
+        );
+
+        let expression = parse_expression(r#"f"{registry.login_server}/node-app:{{v1}}""#).unwrap();
+        assert_eq!(
+            json!("${registry.loginServer}/node-app:{v1}"),
+            to_value(&expression)
+        );
+
+        let expression = parse_expression(
+            r#"pulumi.Output.format("{0}/app:{1}", registry.login_server, "v" + "1")"#,
+        )
+        .unwrap();
+        assert_eq!(
+            json!("${registry.loginServer}/app:v1"),
+            to_value(&expression)
+        );
+
+        let expression = parse_expression("[c for c in containers] if enabled else None").unwrap();
+        assert_eq!(json!("[...] if enabled else None"), to_value(&expression));
+    }
+
+    #[test]
+    fn test_get_images() {
+        let data = r#"
+my_image = docker.Image("node-app",
+    image_name=registry.login_server.apply(lambda server: f"{server}/node-app:v1.0.0"),
+    build=docker.DockerBuildArgs(context="./node-app"),
+)
+
+other_image = docker.Image("other", image_name="other", build="./other")
+
+docker.Image("unused", image_name="unused", build="./unused")
+remote_image = docker.Image("remote", image_name="nginx")
+"#;
+
+        let output = get_images(&tokenize(data).unwrap());
+
+        let expected = vec![
+            ContainerImageBluePrint {
+                name: Some("registry.login_server.apply(lambda: ...)".to_string()),
+                build: BuildContextBluePrint {
+                    context: "./node-app".to_string(),
+                    ..Default::default()
+                },
+                reference_name: Some("my_image".to_string()),
+            },
+            ContainerImageBluePrint {
+                name: Some("other".to_string()),
+                build: BuildContextBluePrint {
+                    context: "./other".to_string(),
+                    ..Default::default()
+                },
+                reference_name: Some("other_image".to_string()),
+            },
+        ];
+
+        assert_eq!(Ok(expected), output);
+    }
+
+    #[test]
+    fn test_get_apps() {
+        let data = r#"
+container_app = azure_native.app.ContainerApp("app",
+    resource_group_name=resource_group.name,
+    managed_environment_id=managed_env.id,
+    configuration=app.ConfigurationArgs(
+        ingress=app.IngressArgs(
+            external=True,
+            target_port=80,
+        ),
+        dapr={"enabled": True, "app_id": "myapp", "app_port": 3000},
+    ),
+    template=app.TemplateArgs(
+        containers=[app.ContainerArgs(
+            name="myapp",
+            image=my_image.image_name,
+        )],
+    ))
+"#;
+
+        let output = get_apps(&tokenize(data).unwrap());
+
+        let expected = vec![ContainerAppBluePrint {
+            configuration: Some(ConfigurationBluePrint {
+                ingress: Some(IngressBluePrint {
+                    external: Some(true),
+                    target_port: Some(80),
+                }),
+                dapr: Some(DaprBluePrint {
+                    app_id: Some("myapp".to_string()),
+                    app_port: Some(3000),
+                    enabled: Some(true),
+                    app_protocol: None,
+                }),
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
+            }),
+            template: Some(TemplateBluePrint {
+                containers: Some(vec![ContainerBluePrint {
+                    name: "myapp".to_string(),
+                    image: "${my_image.imageName}".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: None,
+                    probes: None,
+                    volume_mounts: None,
+                }]),
+                scale: None,
+                volumes: None,
+            }),
+        }];
+
+        assert_eq!(Ok(expected), output);
+    }
+
+    #[test]
+    fn test_get_imports() {
+        let data = "import pulumi\nfrom .apps import web as w, api\nimport infra.images, os\n";
+
+        let output = get_imports(&tokenize(data).unwrap());
+
+        let expected = vec![
+            ("pulumi".to_string(), vec![]),
+            (
+                ".apps".to_string(),
+                vec!["web".to_string(), "api".to_string()],
+            ),
+            ("infra.images".to_string(), vec![]),
+            ("os".to_string(), vec![]),
+        ];
+
+        assert_eq!(expected, output);
+    }
+}
@@ -1,12 +1,23 @@
-use log::error;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+
+use log::{error, warn};
 use serde::Deserialize;
 use serde_yaml::{Mapping, Value};
 
 use crate::pulumi;
+use crate::pulumi::config::Config;
 use crate::serializer::{
     ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
 };
 
+const IMAGE_TYPES: [&str; 3] = ["docker:RegistryImage", "docker:Image", "docker-build:Image"];
+// Jobs share the properties of container apps, with a trigger instead of an ingress
+const APP_TYPES: [&str; 2] = ["azure-native:app:ContainerApp", "azure-native:app:Job"];
+const DAPR_COMPONENT_TYPE: &str = "azure-native:app:DaprComponent";
+
 fn filter_by_type(val: &&Value, resource_type: &str) -> bool {
     match val.get("type") {
         Some(x) => x.as_str() == Some(resource_type),
@@ -14,55 +25,394 @@ fn filter_by_type(val: &&Value, resource_type: &str) -> bool {
     }
 }
 
+fn is_image(resource: &Value) -> bool {
+    IMAGE_TYPES
+        .iter()
+        .any(|image_type| filter_by_type(&resource, image_type))
+}
+
 fn get_images(mapping: &Mapping) -> Vec<ContainerImageBluePrint> {
     mapping
-        .keys()
-        .map(|key| match mapping.get(key) {
-            Some(resource) => {
-                if filter_by_type(&resource, "docker:RegistryImage") {
-                    let mut image: ContainerImageBluePrint =
-                        serde_yaml::from_value(resource.get("properties").unwrap().to_owned())
-                            .unwrap();
-                    image.reference_name = Some(key.as_str().unwrap().to_string());
-
-                    Some(image)
-                } else {
-                    None
-                }
-            }
-            None => None,
+        .iter()
+        .filter(|(_, resource)| is_image(resource))
+        .filter_map(|(key, resource)| {
+            let properties = serde_json::to_value(resource.get("properties")?).ok()?;
+            pulumi::image::parse_image(key.as_str().map(|key| key.to_string()), &properties)
         })
-        .flatten()
         .collect()
 }
 
 fn get_apps(mapping: &Mapping) -> Vec<ContainerAppBluePrint> {
     mapping
         .values()
-        .filter(|x| filter_by_type(x, "azure-native:app:ContainerApp"))
+        .filter(|x| APP_TYPES.iter().any(|app_type| filter_by_type(x, app_type)))
         .map(|container| {
             serde_yaml::from_value(container.get("properties").unwrap().to_owned()).unwrap()
         })
         .collect()
 }
 
-pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String> {
+fn get_components(mapping: &Mapping) -> Vec<DaprComponentBluePrint> {
+    mapping
+        .iter()
+        .filter(|(_, resource)| filter_by_type(resource, DAPR_COMPONENT_TYPE))
+        .filter_map(|(key, resource)| {
+            let properties = serde_json::to_value(resource.get("properties")?).ok()?;
+            pulumi::parse_dapr_component(key.as_str().map(|key| key.to_string()), properties)
+        })
+        .collect()
+}
+
+fn config_to_value(value: &serde_json::Value) -> Value {
+    match value {
+        // Stack values are saved as strings
+        serde_json::Value::String(s) => match (s.parse::<i64>(), s.parse::<bool>()) {
+            (Ok(number), _) => Value::from(number),
+            (_, Ok(boolean)) => Value::from(boolean),
+            _ => Value::from(s.to_string()),
+        },
+        value => serde_yaml::to_value(value).unwrap_or(Value::Null),
+    }
+}
+
+// Values only known once deployed, eg: `<fn::invoke azure-native:containerregistry:listRegistryCredentials.username>`
+const PLACEHOLDER_PREFIX: &str = "<fn::invoke ";
+
+#[derive(Debug, PartialEq)]
+enum Accessor {
+    Property(String),
+    Index(usize),
+}
+
+/***
+ * Property path of an interpolation, eg: `${adminPasswords[0].value}` or `${resource["name"]}`
+ */
+fn parse_path(path: &str) -> Option<(String, Vec<Accessor>)> {
+    let chars: Vec<char> = path.trim().chars().collect();
+    let mut position = 0;
+    let read_name = |position: &mut usize| {
+        let start = *position;
+        while *position < chars.len() && !['.', '['].contains(&chars[*position]) {
+            *position += 1;
+        }
+        chars[start..*position].iter().collect::<String>()
+    };
+
+    let root = read_name(&mut position);
+    if root.is_empty() {
+        return None;
+    }
+
+    let mut accessors = vec![];
+    while position < chars.len() {
+        if chars[position] == '.' {
+            position += 1;
+            accessors.push(Accessor::Property(read_name(&mut position)));
+            continue;
+        }
+
+        let start = position + 1;
+        while position < chars.len() && chars[position] != ']' {
+            position += 1;
+        }
+        let index: String = chars.get(start..position)?.iter().collect();
+        position += 1;
+
+        let accessor = match index.trim().parse::<usize>() {
+            Ok(index) => Accessor::Index(index),
+            Err(_) => Accessor::Property(index.trim().trim_matches('"').to_string()),
+        };
+        accessors.push(accessor);
+    }
+
+    Some((root, accessors))
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Null => "".to_string(),
+        value => serde_yaml::to_string(value)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn to_base64(input: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::new();
+
+    for chunk in input.as_bytes().chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let number = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                output.push(ALPHABET[(number >> (18 - index * 6) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+/***
+ * Pulumi YAML expressions, variables are evaluated when they are first used
+ */
+struct Evaluator<'a> {
+    program: &'a Value,
+    config: &'a Config,
+    values: RefCell<HashMap<String, Option<Value>>>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(program: &'a Value, config: &'a Config) -> Evaluator<'a> {
+        Evaluator {
+            program,
+            config,
+            values: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn evaluate(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => self.interpolate(s),
+            Value::Mapping(mapping) => match mapping.iter().next() {
+                Some((Value::String(name), argument))
+                    if mapping.len() == 1 && name.starts_with("fn::") =>
+                {
+                    self.call_function(name, argument)
+                        .unwrap_or_else(|| value.to_owned())
+                }
+                _ => Value::Mapping(
+                    mapping
+                        .iter()
+                        .map(|(key, value)| (key.to_owned(), self.evaluate(value)))
+                        .collect(),
+                ),
+            },
+            Value::Sequence(values) => {
+                Value::Sequence(values.iter().map(|value| self.evaluate(value)).collect())
+            }
+            value => value.to_owned(),
+        }
+    }
+
+    /***
+     * Replace `${...}` interpolations, a value made of a single interpolation keeps its type
+     */
+    fn interpolate(&self, input: &str) -> Value {
+        let mut output = String::new();
+        let mut rest = input;
+
+        while let Some(start) = rest.find("${") {
+            // `$${` escapes an interpolation
+            if rest[..start].ends_with('$') {
+                output.push_str(&rest[..start - 1]);
+                output.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+                break;
+            };
+            output.push_str(&rest[..start]);
+
+            let expression = &rest[start..=end];
+            let value = self.resolve_path(&rest[start + 2..end]);
+            if output.is_empty() && end + 1 == rest.len() {
+                return value.unwrap_or_else(|| Value::from(expression));
+            }
+
+            match value {
+                Some(value) => output.push_str(&value_to_string(&value)),
+                None => output.push_str(expression),
+            }
+            rest = &rest[end + 1..];
+        }
+        output.push_str(rest);
+
+        Value::String(output)
+    }
+
+    fn resolve_path(&self, path: &str) -> Option<Value> {
+        let (root, accessors) = parse_path(path)?;
+        let mut value = self.get_root(&root)?;
+
+        for accessor in accessors {
+            value = match (&value, &accessor) {
+                (Value::String(placeholder), accessor)
+                    if placeholder.starts_with(PLACEHOLDER_PREFIX) =>
+                {
+                    let accessor = match accessor {
+                        Accessor::Property(property) => format!(".{}", property),
+                        Accessor::Index(index) => format!("[{}]", index),
+                    };
+                    Value::String(format!(
+                        "{}{}>",
+                        placeholder.trim_end_matches('>'),
+                        accessor
+                    ))
+                }
+                (value, Accessor::Property(property)) => value.get(property)?.to_owned(),
+                (value, Accessor::Index(index)) => value.get(index)?.to_owned(),
+            };
+        }
+
+        Some(value)
+    }
+
+    // Variables, then configuration, then resource inputs
+    fn get_root(&self, name: &str) -> Option<Value> {
+        if let Some(variable) = self.program.get("variables").and_then(|v| v.get(name)) {
+            return self.get_value(&format!("variables.{}", name), variable);
+        }
+        if let Some(value) = self.config.get(None, name) {
+            return Some(config_to_value(value));
+        }
+
+        let resource = self.program.get("resources")?.get(name)?;
+        // Images are linked to the apps by their reference
+        if is_image(resource) {
+            return None;
+        }
+        self.get_value(&format!("resources.{}", name), resource.get("properties")?)
+    }
+
+    fn get_value(&self, key: &str, value: &Value) -> Option<Value> {
+        if let Some(value) = self.values.borrow().get(key) {
+            if value.is_none() {
+                warn!("{} references itself", key);
+            }
+            return value.to_owned();
+        }
+
+        // Marked while evaluated to stop cycles
+        self.values.borrow_mut().insert(key.to_string(), None);
+        let value = self.evaluate(value);
+        self.values
+            .borrow_mut()
+            .insert(key.to_string(), Some(value.to_owned()));
+
+        Some(value)
+    }
+
+    fn call_function(&self, name: &str, argument: &Value) -> Option<Value> {
+        if name == "fn::invoke" {
+            let function = argument
+                .get("function")
+                .map(value_to_string)
+                .unwrap_or_default();
+            warn!(
+                "{} is only known once deployed, a placeholder is used",
+                function
+            );
+
+            let placeholder = match argument.get("return") {
+                Some(field) => format!(
+                    "{}{}.{}>",
+                    PLACEHOLDER_PREFIX,
+                    function,
+                    value_to_string(field)
+                ),
+                None => format!("{}{}>", PLACEHOLDER_PREFIX, function),
+            };
+            return Some(Value::String(placeholder));
+        }
+
+        let argument = self.evaluate(argument);
+        let arguments = argument.as_sequence();
+
+        match name {
+            "fn::join" => {
+                let delimiter = value_to_string(arguments?.first()?);
+                let values: Vec<String> = arguments?
+                    .get(1)?
+                    .as_sequence()?
+                    .iter()
+                    .map(value_to_string)
+                    .collect();
+                Some(Value::String(values.join(&delimiter)))
+            }
+            "fn::split" => {
+                let delimiter = value_to_string(arguments?.first()?);
+                let source = value_to_string(arguments?.get(1)?);
+                Some(Value::Sequence(
+                    source.split(&delimiter).map(Value::from).collect(),
+                ))
+            }
+            "fn::select" => {
+                let index = value_to_string(arguments?.first()?).parse::<usize>().ok()?;
+                arguments?.get(1)?.get(index).cloned()
+            }
+            "fn::toBase64" => Some(Value::String(to_base64(&value_to_string(&argument)))),
+            "fn::secret" => Some(argument),
+            "fn::readFile" => {
+                let path = self.config.folder.join(value_to_string(&argument));
+                match fs::read_to_string(&path) {
+                    Ok(content) => Some(Value::String(content)),
+                    Err(e) => {
+                        warn!("{} cannot be read: {}", path.display(), e);
+                        None
+                    }
+                }
+            }
+            _ => {
+                warn!("{} is not supported", name);
+                None
+            }
+        }
+    }
+}
+
+// The program declares its own configuration with defaults, eg: `configuration: { appPort: { type: Number, default: 3000 } }`
+fn get_config(program: &Value, config: &Config) -> Config {
+    let mut config = config.clone();
+    if config.project.is_empty() {
+        config.project = program
+            .get("name")
+            .and_then(|name| name.as_str())
+            .unwrap_or_default()
+            .to_string();
+    }
+
+    for section in ["config", "configuration"] {
+        if let Some(declarations) = program
+            .get(section)
+            .and_then(|declarations| serde_json::to_value(declarations).ok())
+        {
+            config.declare(&declarations);
+        }
+    }
+
+    config
+}
+
+pub fn deserialize(input: &str, config: &Config) -> Result<Vec<ContainerAppConfiguration>, String> {
     let deserialized_map = serde_yaml::Deserializer::from_str(input);
     let value = Value::deserialize(deserialized_map);
 
     match value {
         Ok(v) => {
+            let config = get_config(&v, config);
             // If resources exists, then iterate over containersApp applications
-            let as_mapping = v
-                .get("resources")
-                .expect("Resources need to be defined")
+            let resources = Evaluator::new(&v, &config)
+                .evaluate(v.get("resources").expect("Resources need to be defined"));
+            let as_mapping = resources
                 .as_mapping()
                 .expect("A mapping need to be generated");
 
             let images: Vec<ContainerImageBluePrint> = get_images(as_mapping);
             let apps: Vec<ContainerAppBluePrint> = get_apps(as_mapping);
+            let components = get_components(as_mapping);
 
-            let services = pulumi::build_configuration(apps, images);
+            let services = pulumi::build_configuration(apps, images, components);
 
             match services {
                 Some(val) => Ok(val),
@@ -77,14 +427,106 @@ pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String
     }
 }
 
+#[cfg(test)]
 mod tests {
     use crate::serializer::{
-        BuildContextBluePrint, ConfigurationBluePrint, ContainerBluePrint, DaprBluePrint,
-        IngressBluePrint, TemplateBluePrint,
+        BuildContextBluePrint, ConfigurationBluePrint, ContainerBluePrint,
+        ContainerResourcesBluePrint, DaprBluePrint, EnvironmentVarBluePrint, IngressBluePrint,
+        SecretBluePrint, TemplateBluePrint,
     };
 
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
     use super::*;
 
+    #[test]
+    fn test_evaluate() {
+        let program = r#"
+        name: capps
+        configuration:
+          appPort:
+            type: Number
+            default: 3000
+          tag:
+            type: String
+        variables:
+          hosts:
+            fn::split: [",", "api.capps.io,web.capps.io"]
+          host:
+            fn::select: [1, "${hosts}"]
+          credentials:
+            fn::invoke:
+              function: azure-native:containerregistry:listRegistryCredentials
+              arguments:
+                registryName: ${registry.name}
+        resources:
+          registry:
+            type: azure-native:containerregistry:Registry
+            properties:
+              name: cappsregistry
+          app:
+            properties:
+              port: ${appPort}
+              image: node:${tag}
+              replicas: ${replicas}
+              server: ${registry.loginServer}
+              registry: ${registry.name}
+              host: https://${host}/$${path}
+              hosts:
+                fn::join: [";", "${hosts}"]
+              token:
+                fn::secret: ${credentials.passwords[0].value}
+              auth:
+                fn::toBase64: user:pwd
+        "#;
+        let program: Value = serde_yaml::from_str(program).unwrap();
+        let config = Config {
+            project: String::new(),
+            folder: PathBuf::new(),
+            values: HashMap::from([
+                ("capps:tag".to_string(), serde_json::json!("18")),
+                ("capps:replicas".to_string(), serde_json::json!("2")),
+            ]),
+        };
+        let config = get_config(&program, &config);
+
+        let output = Evaluator::new(&program, &config).evaluate(&program["resources"]["app"]);
+
+        let expected: Value = serde_yaml::from_str(
+            r#"
+            properties:
+              port: 3000
+              image: node:18
+              replicas: 2
+              server: ${registry.loginServer}
+              registry: cappsregistry
+              host: https://web.capps.io/${path}
+              hosts: api.capps.io;web.capps.io
+              token: <fn::invoke azure-native:containerregistry:listRegistryCredentials.passwords[0].value>
+              auth: dXNlcjpwd2Q=
+            "#,
+        )
+        .unwrap();
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            Some((
+                "credentials".to_string(),
+                vec![
+                    Accessor::Property("passwords".to_string()),
+                    Accessor::Index(0),
+                    Accessor::Property("value".to_string()),
+                ]
+            )),
+            parse_path("credentials.passwords[0][\"value\"]")
+        );
+        assert_eq!(None, parse_path(""));
+    }
+
     #[test]
     fn test_get_images() {
         let images = r#"
@@ -97,6 +539,37 @@ mod tests {
               context: ${pulumi.cwd}/node-app
           options:
             provider: ${provider}
+        apiImage:
+          type: docker:Image
+          properties:
+            imageName: ${registry.loginServer}/api:v1.0.0
+            build:
+              context: ${pulumi.cwd}/api
+              dockerfile: ${pulumi.cwd}/api/docker/Dockerfile.prod
+              args:
+                NODE_VERSION: 18
+              target: runtime
+              platform: linux/amd64
+        workerImage:
+          type: docker-build:Image
+          properties:
+            tags:
+              - ${registry.loginServer}/worker:v1.0.0
+            context:
+              location: ./worker
+            dockerfile:
+              location: ./worker/Dockerfile
+            buildArgs:
+              MODE: release
+            platforms:
+              - linux/amd64
+              - linux/arm64
+            push: true
+        pulledImage:
+          type: docker:Image
+          properties:
+            imageName: nginx:latest
+            skipPush: true
         myImageNotWorking:
           type: docker:RegistryImageNotWorking
           properties:
@@ -117,13 +590,45 @@ mod tests {
 
         let output = get_images(as_mapping);
 
-        let expected = vec![ContainerImageBluePrint {
-            reference_name: Some("myImage".to_string()),
-            name: Some("${registry.loginServer}/node-app:v1.0.0".to_string()),
-            build: BuildContextBluePrint {
-                context: "${pulumi.cwd}/node-app".to_string(),
+        let expected = vec![
+            ContainerImageBluePrint {
+                reference_name: Some("myImage".to_string()),
+                name: Some("${registry.loginServer}/node-app:v1.0.0".to_string()),
+                build: BuildContextBluePrint {
+                    context: "${pulumi.cwd}/node-app".to_string(),
+                    ..Default::default()
+                },
             },
-        }];
+            ContainerImageBluePrint {
+                reference_name: Some("apiImage".to_string()),
+                name: Some("${registry.loginServer}/api:v1.0.0".to_string()),
+                build: BuildContextBluePrint {
+                    context: "${pulumi.cwd}/api".to_string(),
+                    dockerfile: Some("${pulumi.cwd}/api/docker/Dockerfile.prod".to_string()),
+                    args: Some(BTreeMap::from([(
+                        "NODE_VERSION".to_string(),
+                        "18".to_string(),
+                    )])),
+                    target: Some("runtime".to_string()),
+                    platforms: Some(vec!["linux/amd64".to_string()]),
+                    ..Default::default()
+                },
+            },
+            ContainerImageBluePrint {
+                reference_name: Some("workerImage".to_string()),
+                name: Some("${registry.loginServer}/worker:v1.0.0".to_string()),
+                build: BuildContextBluePrint {
+                    context: "./worker".to_string(),
+                    dockerfile: Some("./worker/Dockerfile".to_string()),
+                    args: Some(BTreeMap::from([(
+                        "MODE".to_string(),
+                        "release".to_string(),
+                    )])),
+                    platforms: Some(vec!["linux/amd64".to_string(), "linux/arm64".to_string()]),
+                    ..Default::default()
+                },
+            },
+        ];
 
         assert_eq!(expected, output);
     }
@@ -143,10 +648,21 @@ mod tests {
                 appPort: 3000
                 enabled: true
                 appId: myapp
+              secrets:
+                - name: password
+                  value: ${adminPasswords[0].value}
             template:
               containers:
                 - image: ${myImage.name}
                   name: myapp
+                  env:
+                    - name: PORT
+                      value: 3000
+                    - name: PASSWORD
+                      secretRef: password
+                  resources:
+                    cpu: 0.5
+                    memory: 1Gi
         containerappnotworking:
           type: azure-native:app:ContainerAppNotWorking
           properties:
@@ -185,13 +701,43 @@ mod tests {
                     app_id: Some("myapp".to_string()),
                     app_port: Some(3000),
                     enabled: Some(true),
+                    app_protocol: None,
                 }),
+                secrets: Some(vec![SecretBluePrint {
+                    name: "password".to_string(),
+                    value: Some("${adminPasswords[0].value}".to_string()),
+                    key_vault_url: None,
+                }]),
+                trigger_type: None,
+                schedule_trigger_config: None,
             }),
             template: Some(TemplateBluePrint {
                 containers: Some(vec![ContainerBluePrint {
                     name: "myapp".to_string(),
                     image: "${myImage.name}".to_string(),
+                    env: Some(vec![
+                        EnvironmentVarBluePrint {
+                            name: "PORT".to_string(),
+                            value: Some("3000".to_string()),
+                            secret_ref: None,
+                        },
+                        EnvironmentVarBluePrint {
+                            name: "PASSWORD".to_string(),
+                            value: None,
+                            secret_ref: Some("password".to_string()),
+                        },
+                    ]),
+                    command: None,
+                    args: None,
+                    resources: Some(ContainerResourcesBluePrint {
+                        cpu: Some("0.5".to_string()),
+                        memory: Some("1Gi".to_string()),
+                    }),
+                    probes: None,
+                    volume_mounts: None,
                 }]),
+                scale: None,
+                volumes: None,
             }),
         }];
 
@@ -219,7 +765,7 @@ mod tests {
                       name: myapp
           "#;
 
-        let output = deserialize(wrong_format);
+        let output = deserialize(wrong_format, &Config::default());
 
         assert_eq!(Err("did not find expected key at line 4 column 15, while parsing a block mapping at line 2 column 11".to_string()), output);
     }
@@ -1,47 +1,254 @@
-use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use log::warn;
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize};
 use serde_yaml::Mapping;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Language {
     Yaml,
     Typescript,
     Javascript,
     Json,
     Bicep,
+    Hcl,
+    Go,
+    Python,
+    CSharp,
     NotSupported,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+// Values are strings for the cloud providers, numbers and booleans written as is are accepted
+fn deserialize_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Scalar {
+        String(String),
+        Number(serde_json::Number),
+        Bool(bool),
+    }
+
+    Ok(
+        Option::<Scalar>::deserialize(deserializer)?.map(|value| match value {
+            Scalar::String(s) => s,
+            Scalar::Number(n) => n.to_string(),
+            Scalar::Bool(b) => b.to_string(),
+        }),
+    )
+}
+
+fn deserialize_strings<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    struct Item(#[serde(deserialize_with = "deserialize_string")] Option<String>);
+
+    Ok(
+        Option::<Vec<Item>>::deserialize(deserializer)?.map(|items| {
+            items
+                .into_iter()
+                .map(|item| item.0.unwrap_or_default())
+                .collect()
+        }),
+    )
+}
+
+// Numbers and booleans can be written as strings, unresolved expressions such as `[parameters('port')]` are dropped
+fn deserialize_scalar<'de, D: Deserializer<'de>, T: Deserialize<'de> + FromStr>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Scalar<T> {
+        Value(T),
+        String(String),
+        Other(IgnoredAny),
+    }
+
+    Ok(match Option::<Scalar<T>>::deserialize(deserializer)? {
+        Some(Scalar::Value(value)) => Some(value),
+        Some(Scalar::String(s)) => match s.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!("Value {} cannot be resolved, it is dropped", s);
+                None
+            }
+        },
+        Some(Scalar::Other(_)) => {
+            warn!("Value of an unexpected type is dropped");
+            None
+        }
+        None => None,
+    })
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct BuildContext {
     pub context: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platforms: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_from: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssh: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DaprBluePrint {
+    #[serde(default, deserialize_with = "deserialize_scalar")]
     pub app_port: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
     pub enabled: Option<bool>,
     pub app_id: Option<String>,
+    /// `http`, `grpc`, `https`, `grpcs` or `h2c`, HTTP when it is not set
+    pub app_protocol: Option<String>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct IngressBluePrint {
+    #[serde(default, deserialize_with = "deserialize_scalar")]
     pub external: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
     pub target_port: Option<u32>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretBluePrint {
+    pub name: String,
+    #[serde(default, deserialize_with = "deserialize_string")]
+    pub value: Option<String>,
+    pub key_vault_url: Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleTriggerConfigBluePrint {
+    pub cron_expression: Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct ConfigurationBluePrint {
     pub ingress: Option<IngressBluePrint>,
     pub dapr: Option<DaprBluePrint>,
+    pub secrets: Option<Vec<SecretBluePrint>>,
+    /// `Manual`, `Schedule` or `Event` for jobs, container apps have none
+    pub trigger_type: Option<String>,
+    pub schedule_trigger_config: Option<ScheduleTriggerConfigBluePrint>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleBluePrint {
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub min_replicas: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub max_replicas: Option<u32>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVolumeItemBluePrint {
+    pub secret_ref: Option<String>,
+    /// File name of the secret, defaults to its name
+    pub path: Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeBluePrint {
+    pub name: String,
+    /// `AzureFile`, `EmptyDir` or `Secret`
+    pub storage_type: Option<String>,
+    /// Name of the storage of the managed environment
+    pub storage_name: Option<String>,
+    /// Secrets mounted as files, all the secrets of the app when omitted
+    pub secrets: Option<Vec<SecretVolumeItemBluePrint>>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeMountBluePrint {
+    pub volume_name: Option<String>,
+    pub mount_path: Option<String>,
+    pub sub_path: Option<String>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TemplateBluePrint {
     pub containers: Option<Vec<ContainerBluePrint>>,
+    pub scale: Option<ScaleBluePrint>,
+    pub volumes: Option<Vec<VolumeBluePrint>>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentVarBluePrint {
+    pub name: String,
+    #[serde(default, deserialize_with = "deserialize_string")]
+    pub value: Option<String>,
+    pub secret_ref: Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContainerResourcesBluePrint {
+    /// Number of cores, eg: `0.5`
+    #[serde(default, deserialize_with = "deserialize_string")]
+    pub cpu: Option<String>,
+    /// Memory with its unit, eg: `1Gi` or `512Mi`
+    #[serde(default, deserialize_with = "deserialize_string")]
+    pub memory: Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HttpGetBluePrint {
+    pub path: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub port: Option<u32>,
+    /// `HTTP` or `HTTPS`
+    pub scheme: Option<String>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TcpSocketBluePrint {
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub port: Option<u32>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeBluePrint {
+    /// `Liveness`, `Readiness` or `Startup`
+    #[serde(rename = "type")]
+    pub probe_type: Option<String>,
+    pub http_get: Option<HttpGetBluePrint>,
+    pub tcp_socket: Option<TcpSocketBluePrint>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub initial_delay_seconds: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub period_seconds: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub timeout_seconds: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_scalar")]
+    pub failure_threshold: Option<u32>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ContainerBluePrint {
     pub image: String,
     pub name: String,
+    pub env: Option<Vec<EnvironmentVarBluePrint>>,
+    /// Entrypoint of the container
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub command: Option<Vec<String>>,
+    /// Arguments of the entrypoint
+    #[serde(default, deserialize_with = "deserialize_strings")]
+    pub args: Option<Vec<String>>,
+    pub resources: Option<ContainerResourcesBluePrint>,
+    pub probes: Option<Vec<ProbeBluePrint>>,
+    #[serde(rename = "volumeMounts")]
+    pub volume_mounts: Option<Vec<VolumeMountBluePrint>>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ContainerAppBluePrint {
@@ -51,9 +258,40 @@ pub struct ContainerAppBluePrint {
     pub template: Option<TemplateBluePrint>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct BuildContextBluePrint {
     pub context: String,
+    pub dockerfile: Option<String>,
+    pub args: Option<BTreeMap<String, String>>,
+    pub target: Option<String>,
+    pub platforms: Option<Vec<String>>,
+    pub cache_from: Option<Vec<String>>,
+    pub labels: Option<BTreeMap<String, String>>,
+    pub ssh: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DaprMetadataBluePrint {
+    pub name: String,
+    #[serde(default, deserialize_with = "deserialize_string")]
+    pub value: Option<String>,
+    /// Name of a secret of the component
+    pub secret_ref: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DaprComponentBluePrint {
+    /// Name the apps use to reach the component, eg: `statestore`
+    pub component_name: Option<String>,
+    /// eg: `state.azure.cosmosdb` or `pubsub.azure.servicebus.topics`
+    pub component_type: Option<String>,
+    pub version: Option<String>,
+    pub metadata: Option<Vec<DaprMetadataBluePrint>>,
+    pub secrets: Option<Vec<SecretBluePrint>>,
+    /// Dapr app ids allowed to use the component, all of them when omitted
+    pub scopes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -63,12 +301,106 @@ pub struct ContainerImageBluePrint {
     pub reference_name: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResourceLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeployResources {
+    pub limits: ResourceLimits,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeployConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replicas: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<DeployResources>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthCheck {
+    pub test: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DependsOnCondition {
+    pub condition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DaprComponentMetadata {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DaprComponentMetadataItem {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DaprComponentSpec {
+    #[serde(rename = "type")]
+    pub component_type: String,
+    pub version: String,
+    pub metadata: Vec<DaprComponentMetadataItem>,
+}
+
+/***
+ * Dapr component file loaded by the sidecars from their resources path
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DaprComponent {
+    pub api_version: String,
+    pub kind: String,
+    pub metadata: DaprComponentMetadata,
+    pub spec: DaprComponentSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+}
+
+/***
+ * Services started before, the long syntax waits for a condition such as `service_healthy`
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum DependsOn {
+    Services(Vec<String>),
+    Conditions(BTreeMap<String, DependsOnCondition>),
+}
+
+/***
+ * Restart policy of a service, compose does not restart the ones which exit by default
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Restart {
+    No,
+    Always,
+    OnFailure,
+    UnlessStopped,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ContainerAppConfiguration {
     #[serde(skip_serializing)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub depends_on: Option<Vec<String>>,
+    pub depends_on: Option<DependsOn>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub networks: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -76,27 +408,56 @@ pub struct ContainerAppConfiguration {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub environment: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<Vec<String>>,
+    /// Variables of secrets, written to the env file to keep them out of the compose file
+    #[serde(skip_serializing)]
+    pub secrets: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ports: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub command: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network_mode: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub build: Option<BuildContext>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deploy: Option<DeployConfiguration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<HealthCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volumes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart: Option<Restart>,
+    /// Files by their path, written next to the compose file, eg: secret volumes or Dapr components
+    #[serde(skip_serializing)]
+    pub files: Option<BTreeMap<String, String>>,
+    /// Dapr sidecar of an app, its image and runtime flags follow the Dapr settings
+    #[serde(skip)]
+    pub is_dapr_sidecar: bool,
 }
 pub trait Serializer {
     type Output;
     fn deserialize_value(&mut self, input: &str) -> Result<&Self::Output, String>;
     fn serialize_value(
         &self,
-        services: &Vec<ContainerAppConfiguration>,
+        services: &[ContainerAppConfiguration],
     ) -> Result<Vec<u8>, serde_yaml::Error> {
-        let as_value = vec![services.clone(), vec![default_configuration()]]
+        // The placement service is only added when the Dapr settings did not configure it
+        let placement = (!services.iter().any(|service| service.name == "placement"))
+            .then(default_configuration);
+        let as_value = [services.to_vec(), placement.into_iter().collect()]
             .concat()
             .iter()
-            .fold(Mapping::new(), |acc, x| cast_struct_as_value(acc, &x));
+            .fold(Mapping::new(), cast_struct_as_value);
 
         let configuration = merge_configuration_with_networks(Mapping::new(), as_value);
+        let configuration = merge_configuration_with_volumes(configuration, services);
 
         match serde_yaml::to_string(&configuration) {
             Ok(v) => Ok(v.as_bytes().to_vec()),
@@ -108,26 +469,154 @@ pub trait Serializer {
 fn cast_struct_as_value(mut acc: Mapping, service: &ContainerAppConfiguration) -> Mapping {
     acc.insert(
         serde_yaml::to_value(&service.name).unwrap(),
-        serde_yaml::to_value(&service).unwrap(),
+        serde_yaml::to_value(service).unwrap(),
     );
     acc
 }
 
+// Images of the Dapr runtime, both are tagged with the same Dapr version
+pub const SIDECAR_IMAGE: &str = "daprio/daprd";
+pub const PLACEMENT_IMAGE: &str = "daprio/dapr";
+pub const DEFAULT_DAPR_VERSION: &str = "latest";
+pub const PLACEMENT_PORT: u32 = 50006;
+
 fn default_configuration() -> ContainerAppConfiguration {
+    placement_configuration(DEFAULT_DAPR_VERSION)
+}
+
+pub fn placement_configuration(version: &str) -> ContainerAppConfiguration {
     ContainerAppConfiguration {
         name: String::from("placement"),
-        ports: Some(vec!["50006:50006".to_string()]),
+        ports: Some(vec![format!("{}:{}", PLACEMENT_PORT, PLACEMENT_PORT)]),
         networks: Some(vec!["dapr-network".to_string()]),
-        image: Some("daprio/dapr".to_string()),
+        image: Some(format!("{}:{}", PLACEMENT_IMAGE, version)),
         command: Some(vec![
             "./placement".to_string(),
             "-port".to_string(),
-            "50006".to_string(),
+            PLACEMENT_PORT.to_string(),
         ]),
         depends_on: None,
         environment: None,
         network_mode: None,
         build: None,
+        deploy: None,
+        env_file: None,
+        secrets: None,
+        entrypoint: None,
+        healthcheck: None,
+        volumes: None,
+        files: None,
+        working_dir: None,
+        profiles: None,
+        restart: None,
+        is_dapr_sidecar: false,
+    }
+}
+
+/***
+ * Local emulators of the services backing Dapr components, started next to the placement service
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BackingService {
+    Redis,
+    Azurite,
+    ServiceBus,
+    CosmosDb,
+    RabbitMq,
+}
+
+impl BackingService {
+    /***
+     * Name of the compose service, the components reach the emulator at this host
+     */
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackingService::Redis => "redis",
+            BackingService::Azurite => "azurite",
+            BackingService::ServiceBus => "servicebus",
+            BackingService::CosmosDb => "cosmosdb",
+            BackingService::RabbitMq => "rabbitmq",
+        }
+    }
+}
+
+// Queues and topics of the Service Bus emulator are declared in its configuration file
+pub const SERVICE_BUS_CONFIG_FILE: &str = "servicebus/Config.json";
+// The SQL Edge database of the Service Bus emulator is only reachable from the compose network
+const SERVICE_BUS_SQL_PASSWORD: &str = "Local-Emul4tor";
+
+pub fn backing_service_configuration(service: BackingService) -> Vec<ContainerAppConfiguration> {
+    let configuration = |image: &str| ContainerAppConfiguration {
+        name: service.name().to_string(),
+        ports: None,
+        networks: Some(vec!["dapr-network".to_string()]),
+        image: Some(image.to_string()),
+        command: None,
+        depends_on: None,
+        environment: None,
+        network_mode: None,
+        build: None,
+        deploy: None,
+        env_file: None,
+        secrets: None,
+        entrypoint: None,
+        healthcheck: None,
+        volumes: None,
+        files: None,
+        working_dir: None,
+        profiles: None,
+        restart: None,
+        is_dapr_sidecar: false,
+    };
+
+    match service {
+        BackingService::Redis => vec![configuration("redis:7-alpine")],
+        BackingService::Azurite => vec![configuration("mcr.microsoft.com/azure-storage/azurite")],
+        BackingService::ServiceBus => {
+            let sql_name = format!("{}-sql", service.name());
+            vec![
+                ContainerAppConfiguration {
+                    depends_on: Some(DependsOn::Services(vec![sql_name.clone()])),
+                    environment: Some(vec![
+                        "ACCEPT_EULA=Y".to_string(),
+                        format!("SQL_SERVER={}", sql_name),
+                        format!("MSSQL_SA_PASSWORD={}", SERVICE_BUS_SQL_PASSWORD),
+                    ]),
+                    volumes: Some(vec![format!(
+                        "./{}:/ServiceBus_Emulator/ConfigFiles/Config.json:ro",
+                        SERVICE_BUS_CONFIG_FILE
+                    )]),
+                    files: Some(BTreeMap::from([(
+                        SERVICE_BUS_CONFIG_FILE.to_string(),
+                        r#"{"UserConfig":{"Namespaces":[{"Name":"sbemulatorns","Queues":[],"Topics":[]}],"Logging":{"Type":"File"}}}"#
+                            .to_string()
+                            + "\n",
+                    )])),
+                    ..configuration("mcr.microsoft.com/azure-messaging/servicebus-emulator")
+                },
+                ContainerAppConfiguration {
+                    name: sql_name,
+                    environment: Some(vec![
+                        "ACCEPT_EULA=Y".to_string(),
+                        format!("MSSQL_SA_PASSWORD={}", SERVICE_BUS_SQL_PASSWORD),
+                    ]),
+                    ..configuration("mcr.microsoft.com/azure-sql-edge")
+                },
+            ]
+        }
+        // The vNext emulator serves HTTP, the other images need their self-signed certificate trusted
+        BackingService::CosmosDb => vec![ContainerAppConfiguration {
+            environment: Some(vec!["PROTOCOL=http".to_string()]),
+            ..configuration("mcr.microsoft.com/cosmosdb/linux/azure-cosmos-emulator:vnext-preview")
+        }],
+        // The default guest user cannot connect from another container
+        BackingService::RabbitMq => vec![ContainerAppConfiguration {
+            environment: Some(vec![
+                "RABBITMQ_DEFAULT_USER=dapr".to_string(),
+                "RABBITMQ_DEFAULT_PASS=dapr".to_string(),
+            ]),
+            ..configuration("rabbitmq:3-management-alpine")
+        }],
     }
 }
 
@@ -160,6 +649,74 @@ fn merge_configuration_with_networks(mut configuration: Mapping, services: Mappi
     configuration
 }
 
+// Named volumes are the ones whose source is not a path or a variable
+fn get_named_volume(volume: &str) -> Option<&str> {
+    let (source, _) = volume.split_once(':')?;
+    (!source.starts_with(['.', '/', '~', '$'])).then_some(source)
+}
+
+fn merge_configuration_with_volumes(
+    mut configuration: Mapping,
+    services: &[ContainerAppConfiguration],
+) -> Mapping {
+    let names: BTreeSet<&str> = services
+        .iter()
+        .flat_map(|service| service.volumes.iter().flatten())
+        .filter_map(|volume| get_named_volume(volume))
+        .collect();
+
+    if !names.is_empty() {
+        let volumes: Mapping = names
+            .into_iter()
+            .map(|name| {
+                (
+                    serde_yaml::to_value(name).unwrap(),
+                    serde_yaml::to_value(Mapping::new()).unwrap(),
+                )
+            })
+            .collect();
+
+        configuration.insert(
+            serde_yaml::to_value("volumes").unwrap(),
+            serde_yaml::to_value(volumes).unwrap(),
+        );
+    }
+
+    configuration
+}
+
+/***
+ * Named volumes are replaced by bind mounts of the sub folders of a local folder, named after the volumes
+ */
+pub fn bind_volumes_to_folder(
+    services: &[ContainerAppConfiguration],
+    folder: &str,
+) -> Vec<ContainerAppConfiguration> {
+    // Compose reads sources without a leading `.` or `/` as volume names
+    let folder = folder.trim_end_matches('/');
+    let folder = if folder.starts_with(['.', '/', '~']) {
+        folder.to_string()
+    } else {
+        format!("./{}", folder)
+    };
+
+    services
+        .iter()
+        .map(|service| ContainerAppConfiguration {
+            volumes: service.volumes.as_ref().map(|volumes| {
+                volumes
+                    .iter()
+                    .map(|volume| match get_named_volume(volume) {
+                        Some(_) => format!("{}/{}", folder, volume),
+                        None => volume.to_string(),
+                    })
+                    .collect()
+            }),
+            ..service.clone()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,7 +736,7 @@ mod tests {
             name: String::from("placement"),
             ports: Some(vec!["50006:50006".to_string()]),
             networks: Some(vec!["dapr-network".to_string()]),
-            image: Some("daprio/dapr".to_string()),
+            image: Some("daprio/dapr:latest".to_string()),
             command: Some(vec![
                 "./placement".to_string(),
                 "-port".to_string(),
@@ -189,6 +746,17 @@ mod tests {
             environment: None,
             network_mode: None,
             build: None,
+            deploy: None,
+            env_file: None,
+            secrets: None,
+            entrypoint: None,
+            healthcheck: None,
+            volumes: None,
+            files: None,
+            working_dir: None,
+            profiles: None,
+            restart: None,
+            is_dapr_sidecar: false,
         };
 
         let output = default_configuration();
@@ -196,6 +764,31 @@ mod tests {
         assert_eq!(expected, output)
     }
 
+    #[test]
+    fn test_backing_service_configuration() {
+        let output = backing_service_configuration(BackingService::ServiceBus);
+
+        assert_eq!(
+            vec!["servicebus", "servicebus-sql"],
+            output
+                .iter()
+                .map(|service| service.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(
+            Some(DependsOn::Services(vec!["servicebus-sql".to_string()])),
+            output[0].depends_on
+        );
+        assert!(output[0]
+            .files
+            .as_ref()
+            .is_some_and(|files| files.contains_key("servicebus/Config.json")));
+        assert_eq!(
+            Some(vec!["dapr-network".to_string()]),
+            backing_service_configuration(BackingService::Redis)[0].networks
+        );
+    }
+
     #[test]
     fn test_merge_configuration_with_networks() {
         let mut expected = Mapping::new();
@@ -227,6 +820,54 @@ mod tests {
         assert_eq!(expected, output)
     }
 
+    #[test]
+    fn test_merge_configuration_with_volumes() {
+        let mut service = default_configuration();
+        service.volumes = Some(vec![
+            "files:/app/data".to_string(),
+            "./secrets/myapp/certificates:/etc/tls:ro".to_string(),
+            "/tmp/cache".to_string(),
+        ]);
+
+        let output = merge_configuration_with_volumes(Mapping::new(), &[service]);
+
+        let mut volumes = Mapping::new();
+        volumes.insert(
+            serde_yaml::to_value("files").unwrap(),
+            serde_yaml::to_value(Mapping::new()).unwrap(),
+        );
+        let mut expected = Mapping::new();
+        expected.insert(
+            serde_yaml::to_value("volumes").unwrap(),
+            serde_yaml::to_value(volumes).unwrap(),
+        );
+        assert_eq!(expected, output);
+
+        let output = merge_configuration_with_volumes(Mapping::new(), &[default_configuration()]);
+        assert_eq!(Mapping::new(), output);
+    }
+
+    #[test]
+    fn test_bind_volumes_to_folder() {
+        let mut service = default_configuration();
+        service.volumes = Some(vec![
+            "files:/app/data".to_string(),
+            "./secrets/myapp/certificates:/etc/tls:ro".to_string(),
+            "/tmp/cache".to_string(),
+        ]);
+
+        let output = bind_volumes_to_folder(&[service], "data/");
+
+        assert_eq!(
+            Some(vec![
+                "./data/files:/app/data".to_string(),
+                "./secrets/myapp/certificates:/etc/tls:ro".to_string(),
+                "/tmp/cache".to_string(),
+            ]),
+            output[0].volumes
+        );
+    }
+
     #[test]
     fn test_serializer() {
         let serializer = TestSerializer {};
@@ -236,19 +877,31 @@ mod tests {
                 image: None,
                 build: Some(BuildContext {
                     context: "./node-app".to_string(),
+                    ..Default::default()
                 }),
                 name: "myapp".to_string(),
-                depends_on: Some(vec!["placement".to_string()]),
+                depends_on: Some(DependsOn::Services(vec!["placement".to_string()])),
                 networks: Some(vec![String::from("dapr-network")]),
                 network_mode: None,
                 environment: None,
                 ports: None,
                 command: None,
+                deploy: None,
+                env_file: None,
+                secrets: None,
+                entrypoint: None,
+                healthcheck: None,
+                volumes: None,
+                files: None,
+                working_dir: None,
+                profiles: None,
+                restart: None,
+                is_dapr_sidecar: false,
             },
             ContainerAppConfiguration {
                 image: Some(String::from("daprio/daprd:edge")),
-                name: format!("myapp_dapr"),
-                depends_on: Some(vec![String::from("myapp")]),
+                name: "myapp_dapr".to_string(),
+                depends_on: Some(DependsOn::Services(vec![String::from("myapp")])),
                 network_mode: Some(format!("service:{}", String::from("myapp"))),
                 environment: None,
                 ports: None,
@@ -264,6 +917,17 @@ mod tests {
                     "placement:50006".to_string(),
                     "air".to_string(),
                 ]),
+                deploy: None,
+                env_file: None,
+                secrets: None,
+                entrypoint: None,
+                healthcheck: None,
+                volumes: None,
+                files: None,
+                working_dir: None,
+                profiles: None,
+                restart: None,
+                is_dapr_sidecar: false,
             },
         ];
 
@@ -293,7 +957,7 @@ services:
   placement:
     networks:
     - dapr-network
-    image: daprio/dapr
+    image: daprio/dapr:latest
     ports:
     - 50006:50006
     command:
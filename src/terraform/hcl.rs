@@ -0,0 +1,773 @@
+use ::hcl::eval::{Context, Evaluate, FuncArgs, FuncDef, ParamType};
+use ::hcl::{Block, Body, Expression, Map, Value};
+use log::{error, warn};
+
+use super::{
+    get_app, get_component, Attributes, CONTAINER_APP_TYPE, DAPR_COMPONENT_TYPE, JOB_TYPE,
+};
+use crate::pulumi;
+use crate::serializer::{
+    ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
+};
+
+const ENVIRONMENT_TYPE: &str = "azurerm_container_app_environment";
+const REGISTRY_TYPE: &str = "azurerm_container_registry";
+// Locals and resources can reference each other, resolve them in a few passes
+const MAX_PASSES: usize = 10;
+
+fn get_blocks<'a>(body: &'a Body, identifier: &'a str) -> impl Iterator<Item = &'a Block> {
+    body.blocks()
+        .filter(move |block| block.identifier() == identifier)
+}
+
+fn get_label(block: &Block, index: usize) -> Option<&str> {
+    block.labels().get(index).map(|label| label.as_str())
+}
+
+fn lower(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::from(
+        args[0].as_str().unwrap_or_default().to_lowercase(),
+    ))
+}
+
+fn upper(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::from(
+        args[0].as_str().unwrap_or_default().to_uppercase(),
+    ))
+}
+
+fn trimspace(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::from(args[0].as_str().unwrap_or_default().trim()))
+}
+
+fn replace(args: FuncArgs) -> Result<Value, String> {
+    let value = args[0].as_str().unwrap_or_default();
+    let from = args[1].as_str().unwrap_or_default();
+    let to = args[2].as_str().unwrap_or_default();
+    Ok(Value::from(value.replace(from, to)))
+}
+
+fn join(args: FuncArgs) -> Result<Value, String> {
+    let separator = args[0].as_str().unwrap_or_default();
+    let values: Vec<String> = args[1]
+        .as_array()
+        .map(|values| values.iter().map(value_to_string).collect())
+        .unwrap_or_default();
+    Ok(Value::from(values.join(separator)))
+}
+
+fn format(args: FuncArgs) -> Result<Value, String> {
+    let mut output = args[0].as_str().unwrap_or_default().to_string();
+    for value in args.variadic_args() {
+        let position = ["%s", "%d", "%v"]
+            .iter()
+            .filter_map(|verb| output.find(verb))
+            .min()
+            .ok_or("Too many arguments for format")?;
+        output.replace_range(position..position + 2, &value_to_string(value));
+    }
+    Ok(Value::from(output))
+}
+
+fn lookup(args: FuncArgs) -> Result<Value, String> {
+    let key = args[1].as_str().unwrap_or_default();
+    match args[0].as_object().and_then(|object| object.get(key)) {
+        Some(value) => Ok(value.clone()),
+        None => args
+            .variadic_args()
+            .next()
+            .cloned()
+            .ok_or(format!("Key {} not found", key)),
+    }
+}
+
+fn coalesce(args: FuncArgs) -> Result<Value, String> {
+    args.iter()
+        .find(|value| !value.is_null() && value.as_str() != Some(""))
+        .cloned()
+        .ok_or("No non-null argument".to_string())
+}
+
+fn merge(args: FuncArgs) -> Result<Value, String> {
+    let mut output = Map::new();
+    for value in args.iter() {
+        if let Some(object) = value.as_object() {
+            output.extend(object.clone());
+        }
+    }
+    Ok(Value::Object(output))
+}
+
+fn tostring(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::from(value_to_string(&args[0])))
+}
+
+fn tonumber(args: FuncArgs) -> Result<Value, String> {
+    match &args[0] {
+        Value::Number(n) => Ok(Value::Number(*n)),
+        value => value_to_string(value)
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|e| e.to_string()),
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Null => "".to_string(),
+        v => ::hcl::format::to_string(v).unwrap_or_default(),
+    }
+}
+
+/***
+ * Values known before `terraform apply`, exposed as HCL variables
+ */
+#[derive(Debug, Default, PartialEq)]
+struct Scope {
+    variables: Map<String, Value>,
+    locals: Map<String, Value>,
+    resources: Map<String, Value>,
+    data: Map<String, Value>,
+}
+
+impl Scope {
+    fn context(&self) -> Context<'_> {
+        let mut context = Context::new();
+        context.declare_var("var", Value::Object(self.variables.clone()));
+        context.declare_var("local", Value::Object(self.locals.clone()));
+        context.declare_var("data", Value::Object(self.data.clone()));
+        for (resource_type, resources) in &self.resources {
+            context.declare_var(resource_type.as_str(), resources.clone());
+        }
+
+        let string = ParamType::String;
+        context.declare_func("lower", FuncDef::new(lower, [string.clone()]));
+        context.declare_func("upper", FuncDef::new(upper, [string.clone()]));
+        context.declare_func("trimspace", FuncDef::new(trimspace, [string.clone()]));
+        context.declare_func(
+            "replace",
+            FuncDef::new(replace, [string.clone(), string.clone(), string.clone()]),
+        );
+        context.declare_func(
+            "join",
+            FuncDef::new(join, [string.clone(), ParamType::array_of(ParamType::Any)]),
+        );
+        context.declare_func(
+            "format",
+            FuncDef::builder()
+                .param(string.clone())
+                .variadic_param(ParamType::Any)
+                .build(format),
+        );
+        context.declare_func(
+            "lookup",
+            FuncDef::builder()
+                .param(ParamType::Any)
+                .param(string)
+                .variadic_param(ParamType::Any)
+                .build(lookup),
+        );
+        context.declare_func(
+            "coalesce",
+            FuncDef::builder()
+                .variadic_param(ParamType::Any)
+                .build(coalesce),
+        );
+        context.declare_func(
+            "merge",
+            FuncDef::builder()
+                .variadic_param(ParamType::Any)
+                .build(merge),
+        );
+        context.declare_func("tostring", FuncDef::new(tostring, [ParamType::Any]));
+        context.declare_func("tonumber", FuncDef::new(tonumber, [ParamType::Any]));
+
+        context
+    }
+}
+
+fn get_variables(body: &Body, var_files: &[String]) -> Result<Map<String, Value>, String> {
+    let context = Context::new();
+    let mut variables = Map::new();
+
+    for block in get_blocks(body, "variable") {
+        let default = block
+            .body()
+            .attributes()
+            .find(|attribute| attribute.key() == "default");
+
+        if let (Some(name), Some(default)) = (get_label(block, 0), default) {
+            match default.expr().evaluate(&context) {
+                Ok(value) => {
+                    variables.insert(name.to_string(), value);
+                }
+                Err(e) => warn!("Cannot resolve default of variable {}: {}", name, e),
+            }
+        }
+    }
+
+    for var_file in var_files {
+        let body: Body = ::hcl::from_str(var_file).map_err(|e| e.to_string())?;
+        for attribute in body.attributes() {
+            let value = attribute
+                .expr()
+                .evaluate(&context)
+                .map_err(|e| e.to_string())?;
+            variables.insert(attribute.key().to_string(), value);
+        }
+    }
+
+    Ok(variables)
+}
+
+/***
+ * Known attributes of a resource or data source, plus the computed ones we can guess
+ */
+fn get_resource_object(block: &Block, resource_type: &str, context: &Context) -> Value {
+    let mut object: Map<String, Value> = block
+        .body()
+        .attributes()
+        .filter_map(|attribute| {
+            let value = attribute.expr().evaluate(context).ok()?;
+            Some((attribute.key().to_string(), value))
+        })
+        .collect();
+
+    if let Some(name) = object.get("name").map(value_to_string) {
+        object.insert(
+            "id".to_string(),
+            Value::from(format!("/providers/{}/{}", resource_type, name)),
+        );
+        if resource_type == REGISTRY_TYPE {
+            object.insert(
+                "login_server".to_string(),
+                Value::from(format!("{}.azurecr.io", name.to_lowercase())),
+            );
+        }
+    }
+
+    Value::Object(object)
+}
+
+fn get_scope(body: &Body, var_files: &[String]) -> Result<Scope, String> {
+    let mut scope = Scope {
+        variables: get_variables(body, var_files)?,
+        ..Scope::default()
+    };
+
+    let mut locals: Vec<(String, &Expression)> = get_blocks(body, "locals")
+        .flat_map(|block| block.body().attributes())
+        .map(|attribute| (attribute.key().to_string(), attribute.expr()))
+        .collect();
+
+    for _ in 0..MAX_PASSES {
+        let (resources, data, resolved) = {
+            let context = scope.context();
+
+            let mut resources = Map::new();
+            let mut data = Map::new();
+            for (identifier, objects) in [("resource", &mut resources), ("data", &mut data)] {
+                for block in get_blocks(body, identifier) {
+                    if let (Some(resource_type), Some(label)) =
+                        (get_label(block, 0), get_label(block, 1))
+                    {
+                        let object = get_resource_object(block, resource_type, &context);
+                        let entry = objects
+                            .entry(resource_type.to_string())
+                            .or_insert_with(|| Value::Object(Map::new()));
+                        if let Value::Object(entry) = entry {
+                            entry.insert(label.to_string(), object);
+                        }
+                    }
+                }
+            }
+
+            let resolved: Vec<(String, Value)> = locals
+                .iter()
+                .filter_map(|(name, expression)| {
+                    Some((name.clone(), expression.evaluate(&context).ok()?))
+                })
+                .collect();
+
+            (resources, data, resolved)
+        };
+
+        let has_progress =
+            !resolved.is_empty() || resources != scope.resources || data != scope.data;
+        locals.retain(|(name, _)| !resolved.iter().any(|(n, _)| n == name));
+        scope.locals.extend(resolved);
+        scope.resources = resources;
+        scope.data = data;
+
+        if !has_progress {
+            break;
+        }
+    }
+
+    for (name, _) in locals {
+        warn!("Cannot resolve local {}", name);
+    }
+
+    Ok(scope)
+}
+
+/***
+ * Evaluated attribute, unresolved expressions are kept as written
+ */
+fn get_attribute(body: &Body, key: &str, context: &Context) -> Option<Value> {
+    let expression = body
+        .attributes()
+        .find(|attribute| attribute.key() == key)?
+        .expr();
+
+    match expression.evaluate(context) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Cannot resolve {}: {}", key, e);
+            match expression {
+                // Templates are kept without their quotes, eg: `${var.tag}`
+                Expression::TemplateExpr(template) => Some(Value::from(template.to_string())),
+                expression => ::hcl::format::to_string(expression).ok().map(Value::from),
+            }
+        }
+    }
+}
+
+/***
+ * Body of a block whose arguments are evaluated in the context of the configuration
+ */
+#[derive(Clone, Copy)]
+struct EvaluatedBody<'a> {
+    body: &'a Body,
+    context: &'a Context<'a>,
+}
+
+impl<'a> Attributes for EvaluatedBody<'a> {
+    fn get_blocks(&self, key: &str) -> Vec<EvaluatedBody<'a>> {
+        self.body
+            .blocks()
+            .filter(|block| block.identifier() == key)
+            .map(|block| EvaluatedBody {
+                body: block.body(),
+                context: self.context,
+            })
+            .collect()
+    }
+
+    fn get_string(&self, key: &str) -> Option<String> {
+        get_attribute(self.body, key, self.context).map(|value| value_to_string(&value))
+    }
+
+    fn get_strings(&self, key: &str) -> Option<Vec<String>> {
+        match get_attribute(self.body, key, self.context)? {
+            Value::Array(values) => Some(values.iter().map(value_to_string).collect()),
+            _ => None,
+        }
+    }
+
+    fn get_u32(&self, key: &str) -> Option<u32> {
+        match get_attribute(self.body, key, self.context)? {
+            Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+            value => value_to_string(&value).parse().ok(),
+        }
+    }
+
+    fn get_bool(&self, key: &str) -> Option<bool> {
+        get_attribute(self.body, key, self.context)?.as_bool()
+    }
+}
+
+fn get_apps(body: &Body, context: &Context) -> Vec<ContainerAppBluePrint> {
+    let environments: Vec<&str> = get_blocks(body, "resource")
+        .filter(|block| get_label(block, 0) == Some(ENVIRONMENT_TYPE))
+        .filter_map(|block| get_label(block, 1))
+        .collect();
+
+    if environments.is_empty() {
+        warn!("No {} declared in this configuration", ENVIRONMENT_TYPE);
+    }
+
+    get_blocks(body, "resource")
+        .filter(|block| matches!(get_label(block, 0), Some(CONTAINER_APP_TYPE | JOB_TYPE)))
+        .map(|block| {
+            if block
+                .body()
+                .attributes()
+                .any(|attribute| attribute.key() == "count" || attribute.key() == "for_each")
+            {
+                warn!(
+                    "count and for_each are not expanded for {}, use a plan output instead",
+                    get_label(block, 1).unwrap_or_default()
+                );
+            }
+
+            get_app(EvaluatedBody {
+                body: block.body(),
+                context,
+            })
+        })
+        .collect()
+}
+
+fn get_components(body: &Body, context: &Context) -> Vec<DaprComponentBluePrint> {
+    get_blocks(body, "resource")
+        .filter(|block| get_label(block, 0) == Some(DAPR_COMPONENT_TYPE))
+        .map(|block| {
+            get_component(EvaluatedBody {
+                body: block.body(),
+                context,
+            })
+        })
+        .collect()
+}
+
+pub fn deserialize(
+    input: &str,
+    files: &[String],
+    var_files: &[String],
+) -> Result<Vec<ContainerAppConfiguration>, String> {
+    // Each file is parsed on its own, the blocks of the module are merged into one body
+    let mut body = Body::default();
+    for file in [input]
+        .into_iter()
+        .chain(files.iter().map(|file| file.as_str()))
+    {
+        match ::hcl::from_str::<Body>(file) {
+            Ok(file) => body.extend(file),
+            Err(e) => {
+                error!("{}", e);
+                return Err(e.to_string());
+            }
+        }
+    }
+
+    let scope = get_scope(&body, var_files)?;
+    let apps = get_apps(&body, &scope.context());
+    let components = get_components(&body, &scope.context());
+    // Registries only host already pushed images, there is nothing to build
+    let images: Vec<ContainerImageBluePrint> = vec![];
+
+    let services = pulumi::build_configuration(apps, images, components);
+
+    match services {
+        Some(val) => Ok(val),
+        None => Err("No container to deserialize".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializer::{
+        ConfigurationBluePrint, ContainerBluePrint, ContainerResourcesBluePrint, DaprBluePrint,
+        DaprMetadataBluePrint, EnvironmentVarBluePrint, IngressBluePrint, ProbeBluePrint,
+        SecretBluePrint, TcpSocketBluePrint, TemplateBluePrint, VolumeBluePrint,
+        VolumeMountBluePrint,
+    };
+
+    use super::*;
+
+    const CONFIGURATION: &str = r#"
+variable "registry_name" {
+  type    = string
+  default = "capps"
+}
+
+variable "tag" {
+  type = string
+}
+
+locals {
+  image_prefix = "${azurerm_container_registry.acr.login_server}/${local.project}"
+  project      = lower("Shop")
+  app_port     = 3000
+}
+
+resource "azurerm_container_registry" "acr" {
+  name = var.registry_name
+  sku  = "Basic"
+}
+
+resource "azurerm_container_app_environment" "env" {
+  name = "env-${local.project}"
+}
+
+resource "azurerm_container_app" "frontend" {
+  name                         = "frontend"
+  container_app_environment_id = azurerm_container_app_environment.env.id
+  revision_mode                = "Single"
+
+  ingress {
+    external_enabled = true
+    target_port      = 80
+
+    traffic_weight {
+      percentage      = 100
+      latest_revision = true
+    }
+  }
+
+  dapr {
+    app_id       = "frontend"
+    app_port     = local.app_port
+    app_protocol = "grpc"
+  }
+
+  secret {
+    name  = "api-key"
+    value = "s3cr3t"
+  }
+
+  template {
+    container {
+      name   = "frontend"
+      image  = "${local.image_prefix}-frontend:${var.tag}"
+      cpu    = 0.5
+      memory = "1Gi"
+      args   = ["--port", local.app_port]
+
+      env {
+        name  = "PORT"
+        value = local.app_port
+      }
+
+      env {
+        name        = "API_KEY"
+        secret_name = "api-key"
+      }
+
+      liveness_probe {
+        transport = "TCP"
+        port      = local.app_port
+      }
+
+      volume_mounts {
+        name = "data"
+        path = "/app/data"
+      }
+    }
+
+    volume {
+      name         = "data"
+      storage_type = "AzureFile"
+      storage_name = "files"
+    }
+  }
+}
+"#;
+
+    #[test]
+    fn test_get_variables() {
+        let body: Body = ::hcl::from_str(CONFIGURATION).unwrap();
+
+        let output = get_variables(&body, &[]).unwrap();
+        assert_eq!(Some(&Value::from("capps")), output.get("registry_name"));
+        assert_eq!(None, output.get("tag"));
+
+        let output = get_variables(&body, &["tag = \"v1\"".to_string()]).unwrap();
+        assert_eq!(Some(&Value::from("v1")), output.get("tag"));
+
+        let output = get_variables(&body, &["tag = ".to_string()]);
+        assert!(output.is_err());
+    }
+
+    #[test]
+    fn test_get_scope() {
+        let body: Body = ::hcl::from_str(CONFIGURATION).unwrap();
+
+        let scope = get_scope(&body, &[]).unwrap();
+
+        assert_eq!(
+            Some(&Value::from("capps.azurecr.io/shop")),
+            scope.locals.get("image_prefix")
+        );
+        assert_eq!(
+            Some(&Value::from(
+                "/providers/azurerm_container_app_environment/env-shop"
+            )),
+            scope.resources[ENVIRONMENT_TYPE]
+                .as_object()
+                .unwrap()
+                .get("env")
+                .and_then(|env| env.as_object().unwrap().get("id"))
+        );
+    }
+
+    #[test]
+    fn test_get_apps() {
+        let body: Body = ::hcl::from_str(CONFIGURATION).unwrap();
+        let scope = get_scope(&body, &["tag = \"v1\"".to_string()]).unwrap();
+
+        let output = get_apps(&body, &scope.context());
+
+        let expected = vec![ContainerAppBluePrint {
+            configuration: Some(ConfigurationBluePrint {
+                ingress: Some(IngressBluePrint {
+                    external: Some(true),
+                    target_port: Some(80),
+                }),
+                dapr: Some(DaprBluePrint {
+                    app_id: Some("frontend".to_string()),
+                    app_port: Some(3000),
+                    enabled: Some(true),
+                    app_protocol: Some("grpc".to_string()),
+                }),
+                secrets: Some(vec![SecretBluePrint {
+                    name: "api-key".to_string(),
+                    value: Some("s3cr3t".to_string()),
+                    key_vault_url: None,
+                }]),
+                trigger_type: None,
+                schedule_trigger_config: None,
+            }),
+            template: Some(TemplateBluePrint {
+                containers: Some(vec![ContainerBluePrint {
+                    name: "frontend".to_string(),
+                    image: "capps.azurecr.io/shop-frontend:v1".to_string(),
+                    env: Some(vec![
+                        EnvironmentVarBluePrint {
+                            name: "PORT".to_string(),
+                            value: Some("3000".to_string()),
+                            secret_ref: None,
+                        },
+                        EnvironmentVarBluePrint {
+                            name: "API_KEY".to_string(),
+                            value: None,
+                            secret_ref: Some("api-key".to_string()),
+                        },
+                    ]),
+                    command: None,
+                    args: Some(vec!["--port".to_string(), "3000".to_string()]),
+                    resources: Some(ContainerResourcesBluePrint {
+                        cpu: Some("0.5".to_string()),
+                        memory: Some("1Gi".to_string()),
+                    }),
+                    probes: Some(vec![ProbeBluePrint {
+                        probe_type: Some("Liveness".to_string()),
+                        http_get: None,
+                        tcp_socket: Some(TcpSocketBluePrint { port: Some(3000) }),
+                        initial_delay_seconds: None,
+                        period_seconds: None,
+                        timeout_seconds: None,
+                        failure_threshold: None,
+                    }]),
+                    volume_mounts: Some(vec![VolumeMountBluePrint {
+                        volume_name: Some("data".to_string()),
+                        mount_path: Some("/app/data".to_string()),
+                        sub_path: None,
+                    }]),
+                }]),
+                scale: None,
+                volumes: Some(vec![VolumeBluePrint {
+                    name: "data".to_string(),
+                    storage_type: Some("AzureFile".to_string()),
+                    storage_name: Some("files".to_string()),
+                    secrets: None,
+                }]),
+            }),
+        }];
+
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_get_components() {
+        let body: Body = ::hcl::from_str(
+            r#"
+resource "azurerm_container_app_environment_dapr_component" "statestore" {
+  name                         = "statestore"
+  container_app_environment_id = azurerm_container_app_environment.env.id
+  component_type               = "state.azure.cosmosdb"
+  version                      = "v1"
+  scopes                       = ["frontend"]
+
+  metadata {
+    name        = "masterKey"
+    secret_name = "cosmos-key"
+  }
+
+  secret {
+    name  = "cosmos-key"
+    value = "s3cr3t"
+  }
+}
+"#,
+        )
+        .unwrap();
+
+        let output = get_components(&body, &Context::new());
+
+        let expected = vec![DaprComponentBluePrint {
+            component_name: Some("statestore".to_string()),
+            component_type: Some("state.azure.cosmosdb".to_string()),
+            version: Some("v1".to_string()),
+            metadata: Some(vec![DaprMetadataBluePrint {
+                name: "masterKey".to_string(),
+                value: None,
+                secret_ref: Some("cosmos-key".to_string()),
+            }]),
+            secrets: Some(vec![SecretBluePrint {
+                name: "cosmos-key".to_string(),
+                value: Some("s3cr3t".to_string()),
+                key_vault_url: None,
+            }]),
+            scopes: Some(vec!["frontend".to_string()]),
+        }];
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_get_u32() {
+        let body: Body = ::hcl::from_str("port = 80\nlarge = 4294967376\nname = \"80\"").unwrap();
+        let context = Context::new();
+        let body = EvaluatedBody {
+            body: &body,
+            context: &context,
+        };
+
+        assert_eq!(Some(80), body.get_u32("port"));
+        assert_eq!(Some(80), body.get_u32("name"));
+        assert_eq!(None, body.get_u32("large"));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let output = deserialize("resource \"x\" {", &[], &[]);
+        assert!(output.is_err());
+
+        let output = deserialize(CONFIGURATION, &[], &["tag = \"v1\"".to_string()]).unwrap();
+        assert_eq!(2, output.len());
+        assert_eq!(
+            Some("capps.azurecr.io/shop-frontend:v1".to_string()),
+            output[0].image
+        );
+        assert_eq!(Some(vec!["80:3000".to_string()]), output[0].ports);
+        assert_eq!(Some(vec!["PORT=3000".to_string()]), output[0].environment);
+        assert_eq!(Some(vec!["API_KEY=s3cr3t".to_string()]), output[0].secrets);
+        assert!(output[0].healthcheck.is_some());
+        assert_eq!(Some(vec!["files:/app/data".to_string()]), output[0].volumes);
+
+        // Variables and locals of another file of the module are resolved
+        let (variables, resources) =
+            CONFIGURATION.split_at(CONFIGURATION.find("resource").unwrap());
+        let output = deserialize(
+            resources,
+            &[variables.to_string()],
+            &["tag = \"v1\"".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            Some("capps.azurecr.io/shop-frontend:v1".to_string()),
+            output[0].image
+        );
+
+        // Apps are kept with the unresolved template of their image
+        let output = deserialize(CONFIGURATION, &[], &[]).unwrap();
+        assert_eq!(2, output.len());
+        assert_eq!(
+            Some("${local.image_prefix}-frontend:${var.tag}".to_string()),
+            output[0].image
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use log::error;
+use serde_json::Value;
+
+use super::{
+    get_app, get_component, Attributes, CONTAINER_APP_TYPE, DAPR_COMPONENT_TYPE, JOB_TYPE,
+};
+use crate::pulumi;
+use crate::serializer::{
+    ContainerAppBluePrint, ContainerAppConfiguration, ContainerImageBluePrint,
+    DaprComponentBluePrint,
+};
+
+/***
+ * Resources of a module and all its child modules, `count`/`for_each` instances are already expanded
+ */
+fn get_resources(module: &Value) -> Vec<&Value> {
+    let mut resources: Vec<&Value> = module
+        .get("resources")
+        .and_then(|resources| resources.as_array())
+        .map(|resources| resources.iter().collect())
+        .unwrap_or_default();
+
+    if let Some(modules) = module.get("child_modules").and_then(|m| m.as_array()) {
+        for child in modules {
+            resources.append(&mut get_resources(child));
+        }
+    }
+
+    resources
+}
+
+impl<'a> Attributes for &'a Value {
+    // Nested blocks are exported as arrays of objects
+    fn get_blocks(&self, key: &str) -> Vec<&'a Value> {
+        self.get(key)
+            .and_then(|blocks| blocks.as_array())
+            .map(|blocks| blocks.iter().collect())
+            .unwrap_or_default()
+    }
+
+    // Numbers such as the cpu are exported as is
+    fn get_string(&self, key: &str) -> Option<String> {
+        match self.get(key)? {
+            Value::String(value) => Some(value.to_string()),
+            value @ (Value::Number(_) | Value::Bool(_)) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    fn get_strings(&self, key: &str) -> Option<Vec<String>> {
+        let values = self.get(key)?.as_array()?;
+        Some(
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(|value| value.to_string()))
+                .collect(),
+        )
+    }
+
+    fn get_u32(&self, key: &str) -> Option<u32> {
+        self.get(key)?
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+    }
+
+    fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key)?.as_bool()
+    }
+}
+
+fn get_apps(root_module: &Value) -> Vec<ContainerAppBluePrint> {
+    get_resources(root_module)
+        .into_iter()
+        .filter(|resource| resource.get("mode").and_then(|m| m.as_str()) == Some("managed"))
+        .filter(|resource| {
+            matches!(
+                resource.get("type").and_then(|t| t.as_str()),
+                Some(CONTAINER_APP_TYPE | JOB_TYPE)
+            )
+        })
+        .filter_map(|resource| resource.get("values"))
+        .map(get_app)
+        .collect()
+}
+
+fn get_components(root_module: &Value) -> Vec<DaprComponentBluePrint> {
+    get_resources(root_module)
+        .into_iter()
+        .filter(|resource| resource.get("mode").and_then(|m| m.as_str()) == Some("managed"))
+        .filter(|resource| {
+            resource.get("type").and_then(|t| t.as_str()) == Some(DAPR_COMPONENT_TYPE)
+        })
+        .filter_map(|resource| resource.get("values"))
+        .map(get_component)
+        .collect()
+}
+
+pub fn deserialize(input: &str) -> Result<Vec<ContainerAppConfiguration>, String> {
+    let value: Value = match serde_json::from_str(input) {
+        Ok(v) => v,
+        Err(e) => {
+            error!("{}", e);
+            return Err(e.to_string());
+        }
+    };
+
+    // A plan exposes `planned_values`, a state exposes `values`
+    let root_module = value
+        .get("planned_values")
+        .or_else(|| value.get("values"))
+        .and_then(|values| values.get("root_module"))
+        .ok_or("No planned values or state values found, use `terraform show -json`")?;
+
+    let apps = get_apps(root_module);
+    // Registries only host already pushed images, there is nothing to build
+    let images: Vec<ContainerImageBluePrint> = vec![];
+
+    let components = get_components(root_module);
+
+    let services = pulumi::build_configuration(apps, images, components);
+
+    match services {
+        Some(val) => Ok(val),
+        None => Err("No container to deserialize".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::serializer::{
+        ConfigurationBluePrint, ContainerBluePrint, ContainerResourcesBluePrint, DaprBluePrint,
+        HttpGetBluePrint, IngressBluePrint, ProbeBluePrint, TemplateBluePrint,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_resources() {
+        let module = json!({
+            "resources": [{ "address": "azurerm_container_app.a" }],
+            "child_modules": [{
+                "address": "module.apps",
+                "resources": [{ "address": "module.apps.azurerm_container_app.b[0]" }],
+                "child_modules": [{
+                    "resources": [{ "address": "module.apps.module.c.azurerm_container_app.c" }]
+                }]
+            }]
+        });
+
+        let output = get_resources(&module);
+
+        assert_eq!(3, output.len());
+        assert_eq!(
+            json!("module.apps.azurerm_container_app.b[0]"),
+            output[1]["address"]
+        );
+    }
+
+    #[test]
+    fn test_get_u32() {
+        let values = json!({ "port": 80, "large": 4294967376u64, "name": "80" });
+
+        assert_eq!(Some(80), (&values).get_u32("port"));
+        assert_eq!(None, (&values).get_u32("large"));
+        assert_eq!(None, (&values).get_u32("name"));
+    }
+
+    #[test]
+    fn test_get_apps() {
+        let module = json!({
+            "resources": [
+                {
+                    "address": "azurerm_container_app.frontend",
+                    "mode": "managed",
+                    "type": "azurerm_container_app",
+                    "name": "frontend",
+                    "values": {
+                        "name": "frontend",
+                        "ingress": [{ "external_enabled": true, "target_port": 80 }],
+                        "dapr": [{ "app_id": "frontend", "app_port": 3000, "app_protocol": "http" }],
+                        "template": [{
+                            "container": [{ "name": "frontend", "image": "capps.azurecr.io/frontend:v1", "cpu": 0.25, "memory": "0.5Gi",
+                                "readiness_probe": [{ "transport": "HTTP", "port": 3000, "path": "/health", "interval_seconds": 10 }] }]
+                        }]
+                    }
+                },
+                {
+                    "address": "data.azurerm_container_app.existing",
+                    "mode": "data",
+                    "type": "azurerm_container_app",
+                    "name": "existing",
+                    "values": {}
+                },
+                {
+                    "address": "azurerm_container_registry.acr",
+                    "mode": "managed",
+                    "type": "azurerm_container_registry",
+                    "name": "acr",
+                    "values": { "login_server": "capps.azurecr.io" }
+                }
+            ]
+        });
+
+        let output = get_apps(&module);
+
+        let expected = vec![ContainerAppBluePrint {
+            configuration: Some(ConfigurationBluePrint {
+                ingress: Some(IngressBluePrint {
+                    external: Some(true),
+                    target_port: Some(80),
+                }),
+                dapr: Some(DaprBluePrint {
+                    app_id: Some("frontend".to_string()),
+                    app_port: Some(3000),
+                    enabled: Some(true),
+                    app_protocol: Some("http".to_string()),
+                }),
+                secrets: None,
+                trigger_type: None,
+                schedule_trigger_config: None,
+            }),
+            template: Some(TemplateBluePrint {
+                containers: Some(vec![ContainerBluePrint {
+                    name: "frontend".to_string(),
+                    image: "capps.azurecr.io/frontend:v1".to_string(),
+                    env: None,
+                    command: None,
+                    args: None,
+                    resources: Some(ContainerResourcesBluePrint {
+                        cpu: Some("0.25".to_string()),
+                        memory: Some("0.5Gi".to_string()),
+                    }),
+                    probes: Some(vec![ProbeBluePrint {
+                        probe_type: Some("Readiness".to_string()),
+                        http_get: Some(HttpGetBluePrint {
+                            path: Some("/health".to_string()),
+                            port: Some(3000),
+                            scheme: Some("HTTP".to_string()),
+                        }),
+                        tcp_socket: None,
+                        initial_delay_seconds: None,
+                        period_seconds: Some(10),
+                        timeout_seconds: None,
+                        failure_threshold: None,
+                    }]),
+                    volume_mounts: None,
+                }]),
+                scale: None,
+                volumes: None,
+            }),
+        }];
+
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let output = deserialize(r#"{ "format_version": "1.2" }"#);
+        assert_eq!(
+            Err("No planned values or state values found, use `terraform show -json`".to_string()),
+            output
+        );
+
+        let plan = r#"{
+            "format_version": "1.2",
+            "planned_values": {
+                "root_module": {
+                    "resources": [{
+                        "address": "azurerm_container_app.worker",
+                        "mode": "managed",
+                        "type": "azurerm_container_app",
+                        "name": "worker",
+                        "values": {
+                            "template": [{ "container": [{ "name": "worker", "image": "worker:v2" }] }]
+                        }
+                    }]
+                }
+            }
+        }"#;
+
+        let output = deserialize(plan).unwrap();
+        assert_eq!(1, output.len());
+        assert_eq!(Some("worker:v2".to_string()), output[0].image);
+
+        let plan = r#"{
+            "format_version": "1.2",
+            "planned_values": {
+                "root_module": {
+                    "resources": [{
+                        "address": "azurerm_container_app_job.nightly",
+                        "mode": "managed",
+                        "type": "azurerm_container_app_job",
+                        "name": "nightly",
+                        "values": {
+                            "schedule_trigger_config": [{ "cron_expression": "0 2 * * *" }],
+                            "template": [{ "container": [{ "name": "nightly", "image": "batch:v1" }] }]
+                        }
+                    }]
+                }
+            }
+        }"#;
+
+        let output = deserialize(plan).unwrap();
+        assert_eq!(2, output.len());
+        assert_eq!(Some(vec!["jobs".to_string()]), output[0].profiles);
+        assert_eq!("nightly_cron", output[1].name);
+    }
+}
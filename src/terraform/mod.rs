@@ -0,0 +1,323 @@
+pub mod hcl;
+pub mod json;
+use crate::serializer::{
+    ConfigurationBluePrint, ContainerAppBluePrint, ContainerAppConfiguration, ContainerBluePrint,
+    ContainerResourcesBluePrint, DaprBluePrint, DaprComponentBluePrint, DaprMetadataBluePrint,
+    EnvironmentVarBluePrint, HttpGetBluePrint, IngressBluePrint, Language, ProbeBluePrint,
+    ScaleBluePrint, ScheduleTriggerConfigBluePrint, SecretBluePrint, Serializer,
+    TcpSocketBluePrint, TemplateBluePrint, VolumeBluePrint, VolumeMountBluePrint,
+};
+use log::error;
+use std::{fs, path::Path};
+
+const CONTAINER_APP_TYPE: &str = "azurerm_container_app";
+const JOB_TYPE: &str = "azurerm_container_app_job";
+const DAPR_COMPONENT_TYPE: &str = "azurerm_container_app_environment_dapr_component";
+// Jobs declare the block of their trigger
+const TRIGGER_BLOCKS: [(&str, &str); 3] = [
+    ("manual_trigger_config", "Manual"),
+    ("schedule_trigger_config", "Schedule"),
+    ("event_trigger_config", "Event"),
+];
+
+pub struct Terraform {
+    language: Language,
+    /// Content of the other configuration files of the module
+    pub files: Vec<String>,
+    /// Content of the .tfvars files, later files take precedence
+    pub variables: Vec<String>,
+    pub resources: Option<Vec<ContainerAppConfiguration>>,
+}
+
+impl Terraform {
+    pub fn new(language: Language) -> Option<Terraform> {
+        match language {
+            Language::Hcl | Language::Json => Some(Terraform {
+                language,
+                files: vec![],
+                variables: vec![],
+                resources: None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Serializer for Terraform {
+    type Output = Terraform;
+    fn deserialize_value(&mut self, input: &str) -> Result<&Self, String> {
+        match self.language {
+            Language::Hcl => match hcl::deserialize(input, &self.files, &self.variables) {
+                Ok(value) => {
+                    self.resources = Some(value);
+                    Ok(self)
+                }
+                Err(err) => Err(err),
+            },
+            Language::Json => match json::deserialize(input) {
+                Ok(value) => {
+                    self.resources = Some(value);
+                    Ok(self)
+                }
+                Err(err) => Err(err),
+            },
+            _ => {
+                error!("Language not supported");
+                Err("An error occured".to_string())
+            }
+        }
+    }
+}
+
+/***
+ * Arguments and nested blocks of a resource, read from the configuration or from the values of a plan
+ */
+trait Attributes: Copy {
+    fn get_blocks(&self, key: &str) -> Vec<Self>;
+    fn get_string(&self, key: &str) -> Option<String>;
+    fn get_strings(&self, key: &str) -> Option<Vec<String>>;
+    fn get_u32(&self, key: &str) -> Option<u32>;
+    fn get_bool(&self, key: &str) -> Option<bool>;
+}
+
+// Variables reference the secrets of the app by their `secret_name`
+fn get_env<T: Attributes>(container: T) -> Option<Vec<EnvironmentVarBluePrint>> {
+    let env: Vec<EnvironmentVarBluePrint> = container
+        .get_blocks("env")
+        .iter()
+        .map(|env| EnvironmentVarBluePrint {
+            name: env.get_string("name").unwrap_or_default(),
+            value: env.get_string("value"),
+            secret_ref: env.get_string("secret_name"),
+        })
+        .collect();
+
+    (!env.is_empty()).then_some(env)
+}
+
+// Probes are declared with one block per type, `transport` is `HTTP`, `HTTPS` or `TCP`
+fn get_probes<T: Attributes>(container: T) -> Option<Vec<ProbeBluePrint>> {
+    let probes: Vec<ProbeBluePrint> = [
+        ("liveness_probe", "Liveness"),
+        ("readiness_probe", "Readiness"),
+        ("startup_probe", "Startup"),
+    ]
+    .into_iter()
+    .flat_map(|(key, probe_type)| {
+        container.get_blocks(key).into_iter().map(move |probe| {
+            let transport = probe.get_string("transport").unwrap_or_default();
+            let port = probe.get_u32("port");
+
+            ProbeBluePrint {
+                probe_type: Some(probe_type.to_string()),
+                http_get: (transport != "TCP").then(|| HttpGetBluePrint {
+                    path: probe.get_string("path"),
+                    port,
+                    scheme: Some(transport.to_string()),
+                }),
+                tcp_socket: (transport == "TCP").then_some(TcpSocketBluePrint { port }),
+                initial_delay_seconds: probe.get_u32("initial_delay"),
+                period_seconds: probe.get_u32("interval_seconds"),
+                timeout_seconds: probe.get_u32("timeout"),
+                failure_threshold: probe.get_u32("failure_count_threshold"),
+            }
+        })
+    })
+    .collect();
+
+    (!probes.is_empty()).then_some(probes)
+}
+
+fn get_volume_mounts<T: Attributes>(container: T) -> Option<Vec<VolumeMountBluePrint>> {
+    let volume_mounts: Vec<VolumeMountBluePrint> = container
+        .get_blocks("volume_mounts")
+        .iter()
+        .map(|mount| VolumeMountBluePrint {
+            volume_name: mount.get_string("name"),
+            mount_path: mount.get_string("path"),
+            sub_path: mount.get_string("sub_path"),
+        })
+        .collect();
+
+    (!volume_mounts.is_empty()).then_some(volume_mounts)
+}
+
+// Secret volumes mount all the secrets of the app
+fn get_volumes<T: Attributes>(template: T) -> Option<Vec<VolumeBluePrint>> {
+    let volumes: Vec<VolumeBluePrint> = template
+        .get_blocks("volume")
+        .iter()
+        .map(|volume| VolumeBluePrint {
+            name: volume.get_string("name").unwrap_or_default(),
+            storage_type: volume.get_string("storage_type"),
+            storage_name: volume.get_string("storage_name"),
+            secrets: None,
+        })
+        .collect();
+
+    (!volumes.is_empty()).then_some(volumes)
+}
+
+/***
+ * Blueprint of an `azurerm_container_app` or an `azurerm_container_app_job`
+ */
+fn get_app<T: Attributes>(resource: T) -> ContainerAppBluePrint {
+    let ingress = resource
+        .get_blocks("ingress")
+        .first()
+        .map(|ingress| IngressBluePrint {
+            external: ingress.get_bool("external_enabled"),
+            target_port: ingress.get_u32("target_port"),
+        });
+
+    // Dapr is enabled as soon as the block is declared
+    let dapr = resource
+        .get_blocks("dapr")
+        .first()
+        .map(|dapr| DaprBluePrint {
+            app_id: dapr.get_string("app_id"),
+            app_port: dapr.get_u32("app_port"),
+            enabled: Some(true),
+            app_protocol: dapr.get_string("app_protocol"),
+        });
+
+    let templates = resource.get_blocks("template");
+    let containers: Vec<ContainerBluePrint> = templates
+        .iter()
+        .flat_map(|template| template.get_blocks("container"))
+        .map(|container| ContainerBluePrint {
+            name: container.get_string("name").unwrap_or_default(),
+            image: container.get_string("image").unwrap_or_default(),
+            env: get_env(container),
+            command: container.get_strings("command"),
+            args: container.get_strings("args"),
+            resources: Some(ContainerResourcesBluePrint {
+                cpu: container.get_string("cpu"),
+                memory: container.get_string("memory"),
+            })
+            .filter(|resources| resources.cpu.is_some() || resources.memory.is_some()),
+            probes: get_probes(container),
+            volume_mounts: get_volume_mounts(container),
+        })
+        .collect();
+
+    let scale = templates
+        .first()
+        .map(|template| ScaleBluePrint {
+            min_replicas: template.get_u32("min_replicas"),
+            max_replicas: template.get_u32("max_replicas"),
+        })
+        .filter(|scale| scale.min_replicas.is_some() || scale.max_replicas.is_some());
+
+    // Sensitive values are not exported by a plan, only their names are known
+    let secrets: Vec<SecretBluePrint> = resource
+        .get_blocks("secret")
+        .iter()
+        .map(|secret| SecretBluePrint {
+            name: secret.get_string("name").unwrap_or_default(),
+            value: secret.get_string("value"),
+            key_vault_url: secret.get_string("key_vault_secret_id"),
+        })
+        .collect();
+    let secrets = (!secrets.is_empty()).then_some(secrets);
+
+    let trigger_type = TRIGGER_BLOCKS
+        .iter()
+        .find(|(key, _)| !resource.get_blocks(key).is_empty())
+        .map(|(_, trigger_type)| trigger_type.to_string());
+    let schedule_trigger_config =
+        resource
+            .get_blocks("schedule_trigger_config")
+            .first()
+            .map(|schedule| ScheduleTriggerConfigBluePrint {
+                cron_expression: schedule.get_string("cron_expression"),
+            });
+
+    ContainerAppBluePrint {
+        configuration: (ingress.is_some()
+            || dapr.is_some()
+            || secrets.is_some()
+            || trigger_type.is_some())
+        .then_some(ConfigurationBluePrint {
+            ingress,
+            dapr,
+            secrets,
+            trigger_type,
+            schedule_trigger_config,
+        }),
+        template: Some(TemplateBluePrint {
+            containers: Some(containers),
+            scale,
+            volumes: templates
+                .first()
+                .and_then(|template| get_volumes(*template)),
+        }),
+    }
+}
+
+/***
+ * Blueprint of an `azurerm_container_app_environment_dapr_component`, its secrets are only known by their names in a plan
+ */
+fn get_component<T: Attributes>(resource: T) -> DaprComponentBluePrint {
+    let metadata: Vec<DaprMetadataBluePrint> = resource
+        .get_blocks("metadata")
+        .iter()
+        .map(|metadata| DaprMetadataBluePrint {
+            name: metadata.get_string("name").unwrap_or_default(),
+            value: metadata.get_string("value"),
+            secret_ref: metadata.get_string("secret_name"),
+        })
+        .collect();
+    let secrets: Vec<SecretBluePrint> = resource
+        .get_blocks("secret")
+        .iter()
+        .map(|secret| SecretBluePrint {
+            name: secret.get_string("name").unwrap_or_default(),
+            value: secret.get_string("value"),
+            key_vault_url: None,
+        })
+        .collect();
+
+    DaprComponentBluePrint {
+        component_name: resource.get_string("name"),
+        component_type: resource.get_string("component_type"),
+        version: resource.get_string("version"),
+        metadata: (!metadata.is_empty()).then_some(metadata),
+        secrets: (!secrets.is_empty()).then_some(secrets),
+        // A plan exports the scopes which are not set as an empty list
+        scopes: resource
+            .get_strings("scopes")
+            .filter(|scopes| !scopes.is_empty()),
+    }
+}
+
+/***
+ * Variable files automatically loaded by Terraform, in their loading order
+ */
+pub fn find_var_files(input: &str) -> Vec<String> {
+    let folder = Path::new(input).parent().unwrap_or_else(|| Path::new("."));
+    let folder = if folder.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        folder
+    };
+
+    let mut auto_files: Vec<String> = match fs::read_dir(folder) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path().to_string_lossy().to_string())
+            .filter(|path| path.ends_with(".auto.tfvars"))
+            .collect(),
+        Err(_) => vec![],
+    };
+    auto_files.sort();
+
+    let default_file = folder.join("terraform.tfvars");
+    let mut files = vec![];
+    if default_file.exists() {
+        files.push(default_file.to_string_lossy().to_string());
+    }
+    files.append(&mut auto_files);
+
+    files
+}